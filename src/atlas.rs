@@ -1,19 +1,20 @@
 use crate::image_view::BstImageView;
-use crate::{misc, Basalt};
+use crate::Basalt;
+use crossbeam::channel::{self, Sender};
 use crossbeam::deque::{Injector, Steal};
 use crossbeam::sync::{Parker, Unparker};
 use ilmenite::ImtWeight;
 use image::{self, GenericImageView};
 use ordered_float::OrderedFloat;
 use parking_lot::{Condvar, Mutex};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
-use std::io::Read;
+use std::io::{Cursor, Read};
 use std::path::PathBuf;
-use std::sync::atomic::{self, AtomicBool};
+use std::sync::atomic::{self, AtomicBool, AtomicU64};
 use std::sync::Arc;
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use vulkano::buffer::cpu_access::CpuAccessibleBuffer;
 use vulkano::buffer::{BufferAccess, BufferUsage as VkBufferUsage};
 use vulkano::command_buffer::{
@@ -64,8 +65,82 @@ fn linear_to_srgb(v: u8) -> u8 {
 	v as u8
 }
 
+#[inline]
+fn srgb_to_linear_f32(v: f32) -> f32 {
+	if v < 0.04045 {
+		v / 12.92
+	} else {
+		((v + 0.055) / 1.055).powf(2.4)
+	}
+}
+
+#[inline]
+fn linear_to_srgb_f32(v: f32) -> f32 {
+	if v < 0.0031308 {
+		v * 12.92
+	} else {
+		(1.055 * v.powf(1.0 / 2.4)) - 0.055
+	}
+}
+
+#[inline]
+fn d16_to_f32(v: u16) -> f32 {
+	v as f32 / u16::max_value() as f32
+}
+
+#[inline]
+fn f32_to_d16(v: f32) -> u16 {
+	(v.max(0.0).min(1.0) * u16::max_value() as f32).round() as u16
+}
+
+/// Convert normalized components of the provided type into either SRGBA or LRGBA. Unlike the
+/// 8-bit conversions alpha is always left as is.
+fn rgba_f32(ty: ImageType, data: Vec<f32>, to_srgb: bool) -> Vec<f32> {
+	let mut out = Vec::with_capacity(data.len() / ty.components() * 4);
+
+	for chunk in data.chunks_exact(ty.components()) {
+		let (rgb, a, is_srgb) = match ty {
+			ImageType::LRGBA => ([chunk[0], chunk[1], chunk[2]], chunk[3], false),
+			ImageType::LRGB => ([chunk[0], chunk[1], chunk[2]], 1.0, false),
+			ImageType::LMono => ([chunk[0], chunk[0], chunk[0]], 1.0, false),
+			ImageType::SRGBA => ([chunk[0], chunk[1], chunk[2]], chunk[3], true),
+			ImageType::SRGB => ([chunk[0], chunk[1], chunk[2]], 1.0, true),
+			ImageType::SMono => ([chunk[0], chunk[0], chunk[0]], 1.0, true),
+			ImageType::Glyph => ([0.0, 0.0, 0.0], chunk[0], to_srgb),
+			ImageType::YUV444 =>
+				(
+					[
+						chunk[0] + (1.402 * (chunk[2] - 0.5)),
+						chunk[0] - (0.344 * (chunk[1] - 0.5)) - (0.714 * (chunk[2] - 0.5)),
+						chunk[0] + (1.772 * (chunk[1] - 0.5)),
+					],
+					1.0,
+					true,
+				),
+		};
+
+		for v in rgb.iter() {
+			out.push(
+				if is_srgb == to_srgb {
+					*v
+				} else if to_srgb {
+					linear_to_srgb_f32(*v)
+				} else {
+					srgb_to_linear_f32(*v)
+				},
+			);
+		}
+
+		out.push(a);
+	}
+
+	out
+}
+
 const CELL_WIDTH: u32 = 32;
 const CELL_PAD: u32 = 5;
+const DEFAULT_COMPACT_THRESHOLD: f32 = 0.5;
+const MIN_LEVEL_SIZE: u32 = 16;
 
 pub type AtlasImageID = u64;
 pub type SubImageID = u64;
@@ -75,6 +150,11 @@ pub enum SubImageCacheID {
 	Path(PathBuf),
 	Url(String),
 	Glyph(String, ImtWeight, u16, OrderedFloat<f32>),
+	/// Frame of an animated image other than the first. The first frame uses the id of the
+	/// animation itself.
+	Frame(Box<SubImageCacheID>, usize),
+	/// Vector image rasterized to fit within the provided width and height.
+	Sized(Box<SubImageCacheID>, u32, u32),
 	None,
 }
 
@@ -136,6 +216,11 @@ pub struct ImageDims {
 #[derive(Debug, Clone)]
 pub enum ImageData {
 	D8(Vec<u8>),
+	/// 16-bit components. Images with this data are stored in a higher precision atlas image.
+	D16(Vec<u16>),
+	/// Normalized floating point components. Images with this data are stored in a higher
+	/// precision atlas image, values outside of `0.0..=1.0` will be clamped.
+	F32(Vec<f32>),
 	#[doc(hidden)]
 	__Nonexhaustive,
 }
@@ -167,6 +252,152 @@ impl ImageType {
 	}
 }
 
+/// How the color values of encoded image data are to be interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageColorSpace {
+	/// Values are encoded with the sRGB transfer function.
+	SRGB,
+	/// Values are linear.
+	Linear,
+}
+
+impl ImageColorSpace {
+	/// Detect the color space from the metadata within the encoded image. `None` is returned
+	/// if the image doesn't specify one that can be understood.
+	fn detect(format: image::ImageFormat, bytes: &[u8]) -> Option<Self> {
+		match format {
+			image::ImageFormat::Png => Self::detect_png(bytes),
+			image::ImageFormat::Jpeg => Self::detect_jpeg(bytes),
+			image::ImageFormat::Tiff => Self::detect_exif(bytes),
+			_ => None,
+		}
+	}
+
+	fn detect_png(bytes: &[u8]) -> Option<Self> {
+		let mut gamma = None;
+		let mut exif = None;
+		let mut i = 8;
+
+		while i + 8 <= bytes.len() {
+			let len = u32::from_be_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]])
+				as usize;
+			let data = bytes.get(i + 8..(i + 8).checked_add(len)?)?;
+
+			match &bytes[i + 4..i + 8] {
+				// sRGB and iCCP take precedence over gAMA.
+				b"sRGB" => return Some(ImageColorSpace::SRGB),
+				b"iCCP" => {
+					// The profile itself is compressed, so only its name is looked at. Nearly
+					// all embedded profiles are gamma encoded unless stated otherwise.
+					let name_len = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+					let name = String::from_utf8_lossy(&data[..name_len]).to_lowercase();
+
+					return if name.contains("linear") {
+						Some(ImageColorSpace::Linear)
+					} else {
+						Some(ImageColorSpace::SRGB)
+					};
+				},
+				b"gAMA" if len == 4 =>
+					gamma = Some(u32::from_be_bytes([data[0], data[1], data[2], data[3]])),
+				b"eXIf" => exif = Self::detect_exif(data),
+				b"IDAT" | b"IEND" => break,
+				_ => (),
+			}
+
+			i += len + 12;
+		}
+
+		match gamma {
+			// gAMA is stored as the file gamma times 100000. sRGB is roughly 45455.
+			Some(gamma) if gamma >= 75000 => Some(ImageColorSpace::Linear),
+			Some(_) => Some(ImageColorSpace::SRGB),
+			None => exif,
+		}
+	}
+
+	fn detect_jpeg(bytes: &[u8]) -> Option<Self> {
+		let mut i = 2;
+
+		while i + 4 <= bytes.len() && bytes[i] == 0xFF {
+			let marker = bytes[i + 1];
+			let len = u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]) as usize;
+
+			// Start of scan, metadata is always before this.
+			if marker == 0xDA || len < 2 {
+				break;
+			}
+
+			let data = bytes.get(i + 4..i + 2 + len)?;
+
+			if marker == 0xE1 && data.starts_with(b"Exif\0\0") {
+				return Self::detect_exif(&data[6..]);
+			}
+
+			i += len + 2;
+		}
+
+		None
+	}
+
+	/// Look up the color space tag within TIFF formatted EXIF data. EXIF only specifies sRGB or
+	/// uncalibrated, the latter of which yields `None`.
+	fn detect_exif(tiff: &[u8]) -> Option<Self> {
+		let be = match tiff.get(0..4)? {
+			b"MM\0*" => true,
+			b"II*\0" => false,
+			_ => return None,
+		};
+
+		let read_u16 = |at: usize| -> Option<u16> {
+			let b = tiff.get(at..at + 2)?;
+
+			Some(
+				if be {
+					u16::from_be_bytes([b[0], b[1]])
+				} else {
+					u16::from_le_bytes([b[0], b[1]])
+				},
+			)
+		};
+
+		let read_u32 = |at: usize| -> Option<u32> {
+			let b = tiff.get(at..at + 4)?;
+
+			Some(
+				if be {
+					u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+				} else {
+					u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+				},
+			)
+		};
+
+		let find_tag = |ifd: usize, tag: u16| -> Option<usize> {
+			let count = read_u16(ifd)? as usize;
+
+			for j in 0..count {
+				let entry = ifd + 2 + (j * 12);
+
+				if read_u16(entry)? == tag {
+					return Some(entry + 8);
+				}
+			}
+
+			None
+		};
+
+		let ifd0 = read_u32(4)? as usize;
+		let exif_ifd = read_u32(find_tag(ifd0, 0x8769)?)? as usize;
+
+		match read_u16(find_tag(exif_ifd, 0xA001)?)? {
+			1 => Some(ImageColorSpace::SRGB),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Clone)]
 pub struct Image {
 	ty: ImageType,
 	dims: ImageDims,
@@ -181,14 +412,24 @@ impl Image {
 			return Err(format!("Image can't be empty"));
 		}
 
-		match &mut data {
-			&mut ImageData::D8(ref mut d) =>
-				if d.len() > expected_len {
-					d.truncate(expected_len);
-				} else if d.len() < expected_len {
-					return Err(format!("Data length doesn't match the provided dimensions."));
-				},
+		let data_len = match &mut data {
+			&mut ImageData::D8(ref mut d) => {
+				d.truncate(expected_len);
+				d.len()
+			},
+			&mut ImageData::D16(ref mut d) => {
+				d.truncate(expected_len);
+				d.len()
+			},
+			&mut ImageData::F32(ref mut d) => {
+				d.truncate(expected_len);
+				d.len()
+			},
 			_ => unreachable!(),
+		};
+
+		if data_len < expected_len {
+			return Err(format!("Data length doesn't match the provided dimensions."));
 		}
 
 		Ok(Image {
@@ -202,163 +443,532 @@ impl Image {
 		self.data
 	}
 
-	pub fn to_srgba(self) -> Self {
-		if let ImageData::D8(data) = self.data {
-			let mut srgba = Vec::with_capacity(data.len() / self.ty.components() * 4);
+	/// Decode an image from encoded bytes.
+	///
+	/// If `color_space` is `None` it is detected from the metadata within the image. For PNG
+	/// this is the `sRGB`, `iCCP`, `gAMA` & `eXIf` chunks and for JPEG the EXIF color space.
+	/// Images without any usable metadata are assumed to be sRGB.
+	pub fn from_bytes(
+		bytes: &[u8],
+		color_space: Option<ImageColorSpace>,
+	) -> Result<Image, String> {
+		let format = match image::guess_format(bytes) {
+			Ok(ok) => ok,
+			Err(e) => return Err(format!("Failed to guess image type for data: {}", e)),
+		};
 
-			match self.ty {
-				ImageType::LRGBA =>
-					for v in data {
-						srgba.push(linear_to_srgb(v));
-					},
-				ImageType::LRGB =>
-					for v in data {
-						srgba.push(linear_to_srgb(v));
+		// Sources with more than 8 bits per component are kept at 16 bits to avoid banding.
+		let (w, h, data) = match image::load_from_memory_with_format(bytes, format) {
+			Ok(image) =>
+				match image.color() {
+					image::ColorType::L16
+					| image::ColorType::La16
+					| image::ColorType::Rgb16
+					| image::ColorType::Rgba16 =>
+						(
+							image.width(),
+							image.height(),
+							ImageData::D16(image.to_rgba16().into_vec()),
+						),
+					_ =>
+						(
+							image.width(),
+							image.height(),
+							ImageData::D8(image.to_rgba8().into_vec()),
+						),
+				},
+			Err(e) => return Err(format!("Failed to read image: {}", e)),
+		};
 
-						if srgba.len() % 4 == 2 {
-							srgba.push(255);
-						}
-					},
-				ImageType::LMono =>
-					for mut v in data {
-						v = linear_to_srgb(v);
-						srgba.push(v);
-						srgba.push(v);
-						srgba.push(v);
-						srgba.push(255);
-					},
-				ImageType::SMono =>
-					for v in data {
-						srgba.push(v);
-						srgba.push(v);
-						srgba.push(v);
-						srgba.push(255);
-					},
-				ImageType::SRGBA => srgba = data,
-				ImageType::SRGB =>
-					for v in data {
-						srgba.push(v);
+		let color_space = color_space
+			.or_else(|| ImageColorSpace::detect(format, bytes))
+			.unwrap_or(ImageColorSpace::SRGB);
 
-						if srgba.len() % 4 == 2 {
-							srgba.push(255);
-						}
-					},
-				ImageType::Glyph =>
-					for v in data {
-						srgba.push(0);
-						srgba.push(0);
-						srgba.push(0);
-						srgba.push(v);
+		let image_type = match color_space {
+			ImageColorSpace::SRGB => ImageType::SRGBA,
+			ImageColorSpace::Linear => ImageType::LRGBA,
+		};
+
+		Image::new(
+			image_type,
+			ImageDims {
+				w,
+				h,
+			},
+			data,
+		)
+		.map_err(|e| format!("Invalid Image: {}", e))
+	}
+
+	/// Rasterize an SVG to fit within `w` by `h` pixels. The aspect ratio of the SVG is kept so
+	/// one of the dimensions of the resulting image may be smaller than requested.
+	pub fn from_svg_bytes(bytes: &[u8], w: u32, h: u32) -> Result<Image, String> {
+		let tree = usvg::Tree::from_data(bytes, &usvg::Options::default().to_ref())
+			.map_err(|e| format!("Failed to read svg: {}", e))?;
+
+		let size = match usvg::FitTo::Size(w, h).fit_to(tree.svg_node().size.to_screen_size()) {
+			Some(some) => some,
+			None => return Err(format!("Invalid size: {}x{}", w, h)),
+		};
+
+		let mut pixmap = match tiny_skia::Pixmap::new(size.width(), size.height()) {
+			Some(some) => some,
+			None => return Err(format!("Invalid size: {}x{}", w, h)),
+		};
+
+		if resvg::render(
+			&tree,
+			usvg::FitTo::Size(w, h),
+			tiny_skia::Transform::identity(),
+			pixmap.as_mut(),
+		)
+		.is_none()
+		{
+			return Err(format!("Failed to render svg."));
+		}
+
+		// Pixmap data is premultiplied.
+		let data = pixmap
+			.data()
+			.chunks_exact(4)
+			.flat_map(|c| {
+				match c[3] {
+					0 => vec![0, 0, 0, 0],
+					a =>
+						vec![
+							((c[0] as u32 * 255) / a as u32) as u8,
+							((c[1] as u32 * 255) / a as u32) as u8,
+							((c[2] as u32 * 255) / a as u32) as u8,
+							a,
+						],
+				}
+			})
+			.collect();
+
+		Image::new(
+			ImageType::SRGBA,
+			ImageDims {
+				w: size.width(),
+				h: size.height(),
+			},
+			ImageData::D8(data),
+		)
+		.map_err(|e| format!("Invalid Image: {}", e))
+	}
+
+	/// Decode all the frames of an animated GIF or APNG along with the delay of each frame.
+	/// Images that aren't animated will result in a single frame. See `Image::from_bytes()` for
+	/// how `color_space` is handled.
+	pub fn frames_from_bytes(
+		bytes: &[u8],
+		color_space: Option<ImageColorSpace>,
+	) -> Result<Vec<(Image, Duration)>, String> {
+		use image::codecs::gif::GifDecoder;
+		use image::codecs::png::PngDecoder;
+		use image::AnimationDecoder;
+
+		let format = match image::guess_format(bytes) {
+			Ok(ok) => ok,
+			Err(e) => return Err(format!("Failed to guess image type for data: {}", e)),
+		};
+
+		let frames = match format {
+			image::ImageFormat::Gif =>
+				GifDecoder::new(Cursor::new(bytes))
+					.and_then(|decoder| decoder.into_frames().collect_frames()),
+			image::ImageFormat::Png =>
+				match PngDecoder::new(Cursor::new(bytes)) {
+					Ok(decoder) if decoder.is_apng() =>
+						decoder.apng().into_frames().collect_frames(),
+					Ok(_) => Ok(Vec::new()),
+					Err(e) => Err(e),
+				},
+			_ => Ok(Vec::new()),
+		}
+		.map_err(|e| format!("Failed to read image: {}", e))?;
+
+		if frames.is_empty() {
+			return Ok(vec![(Image::from_bytes(bytes, color_space)?, Duration::from_secs(0))]);
+		}
+
+		let image_type = match color_space
+			.or_else(|| ImageColorSpace::detect(format, bytes))
+			.unwrap_or(ImageColorSpace::SRGB)
+		{
+			ImageColorSpace::SRGB => ImageType::SRGBA,
+			ImageColorSpace::Linear => ImageType::LRGBA,
+		};
+
+		frames
+			.into_iter()
+			.map(|frame| {
+				let (numer, denom) = frame.delay().numer_denom_ms();
+				let mut delay =
+					Duration::from_micros((numer as u64 * 1000) / denom.max(1) as u64);
+
+				// Same as browsers, very short delays are treated as unspecified.
+				if delay <= Duration::from_millis(10) {
+					delay = Duration::from_millis(100);
+				}
+
+				let buffer = frame.into_buffer();
+
+				let image = Image::new(
+					image_type,
+					ImageDims {
+						w: buffer.width(),
+						h: buffer.height(),
 					},
-				ImageType::YUV444 =>
-					for chunk in data.chunks_exact(3) {
-						let components = [
-							chunk[0] as f32 + (1.402 * (chunk[2] as f32 - 128.0)),
-							chunk[0] as f32 + (0.344 * (chunk[1] as f32 - 128.0))
-								- (0.714 * (chunk[2] as f32 - 128.0)),
-							chunk[0] as f32 + (1.772 * (chunk[1] as f32 - 128.0)),
-						];
-
-						for v in &components {
-							srgba.push(*v as u8);
-						}
+					ImageData::D8(buffer.into_raw()),
+				)
+				.map_err(|e| format!("Invalid Image: {}", e))?;
+
+				Ok((image, delay))
+			})
+			.collect()
+	}
+
+	pub fn to_srgba(self) -> Self {
+		let data = match self.data {
+			ImageData::D8(data) => data,
+			ImageData::D16(data) =>
+				return Image {
+					ty: ImageType::SRGBA,
+					dims: self.dims,
+					data: ImageData::D16(
+						rgba_f32(self.ty, data.into_iter().map(d16_to_f32).collect(), true)
+							.into_iter()
+							.map(f32_to_d16)
+							.collect(),
+					),
+				},
+			ImageData::F32(data) =>
+				return Image {
+					ty: ImageType::SRGBA,
+					dims: self.dims,
+					data: ImageData::F32(rgba_f32(self.ty, data, true)),
+				},
+			ImageData::__Nonexhaustive => unreachable!(),
+		};
+
+		let mut srgba = Vec::with_capacity(data.len() / self.ty.components() * 4);
+
+		match self.ty {
+			ImageType::LRGBA =>
+				for v in data {
+					srgba.push(linear_to_srgb(v));
+				},
+			ImageType::LRGB =>
+				for v in data {
+					srgba.push(linear_to_srgb(v));
 
+					if srgba.len() % 4 == 2 {
 						srgba.push(255);
-					},
-			}
+					}
+				},
+			ImageType::LMono =>
+				for mut v in data {
+					v = linear_to_srgb(v);
+					srgba.push(v);
+					srgba.push(v);
+					srgba.push(v);
+					srgba.push(255);
+				},
+			ImageType::SMono =>
+				for v in data {
+					srgba.push(v);
+					srgba.push(v);
+					srgba.push(v);
+					srgba.push(255);
+				},
+			ImageType::SRGBA => srgba = data,
+			ImageType::SRGB =>
+				for v in data {
+					srgba.push(v);
 
-			Image {
-				ty: ImageType::SRGBA,
-				dims: self.dims,
-				data: ImageData::D8(srgba),
-			}
-		} else {
-			unreachable!()
+					if srgba.len() % 4 == 2 {
+						srgba.push(255);
+					}
+				},
+			ImageType::Glyph =>
+				for v in data {
+					srgba.push(0);
+					srgba.push(0);
+					srgba.push(0);
+					srgba.push(v);
+				},
+			ImageType::YUV444 =>
+				for chunk in data.chunks_exact(3) {
+					let components = [
+						chunk[0] as f32 + (1.402 * (chunk[2] as f32 - 128.0)),
+						chunk[0] as f32 + (0.344 * (chunk[1] as f32 - 128.0))
+							- (0.714 * (chunk[2] as f32 - 128.0)),
+						chunk[0] as f32 + (1.772 * (chunk[1] as f32 - 128.0)),
+					];
+
+					for v in &components {
+						srgba.push(*v as u8);
+					}
+
+					srgba.push(255);
+				},
+		}
+
+		Image {
+			ty: ImageType::SRGBA,
+			dims: self.dims,
+			data: ImageData::D8(srgba),
 		}
 	}
 
 	pub fn to_lrgba(self) -> Self {
-		if let ImageData::D8(data) = self.data {
-			let mut lrgba = Vec::with_capacity(data.len() / self.ty.components() * 4);
+		let data = match self.data {
+			ImageData::D8(data) => data,
+			ImageData::D16(data) =>
+				return Image {
+					ty: ImageType::LRGBA,
+					dims: self.dims,
+					data: ImageData::D16(
+						rgba_f32(self.ty, data.into_iter().map(d16_to_f32).collect(), false)
+							.into_iter()
+							.map(f32_to_d16)
+							.collect(),
+					),
+				},
+			ImageData::F32(data) =>
+				return Image {
+					ty: ImageType::LRGBA,
+					dims: self.dims,
+					data: ImageData::F32(rgba_f32(self.ty, data, false)),
+				},
+			ImageData::__Nonexhaustive => unreachable!(),
+		};
 
-			match self.ty {
-				ImageType::LRGBA => lrgba = data,
-				ImageType::LRGB =>
-					for v in data {
-						lrgba.push(v);
+		let mut lrgba = Vec::with_capacity(data.len() / self.ty.components() * 4);
 
-						if lrgba.len() % 4 == 2 {
-							lrgba.push(255);
-						}
-					},
-				ImageType::LMono =>
-					for v in data {
-						lrgba.push(v);
-						lrgba.push(v);
-						lrgba.push(v);
-						lrgba.push(255);
-					},
-				ImageType::SMono =>
-					for mut v in data {
-						v = srgb_to_linear_d8(v);
-						lrgba.push(v);
-						lrgba.push(v);
-						lrgba.push(v);
+		match self.ty {
+			ImageType::LRGBA => lrgba = data,
+			ImageType::LRGB =>
+				for v in data {
+					lrgba.push(v);
+
+					if lrgba.len() % 4 == 2 {
 						lrgba.push(255);
-					},
-				ImageType::SRGBA =>
-					for v in data {
-						lrgba.push(srgb_to_linear_d8(v));
-					},
-				ImageType::SRGB =>
-					for v in data {
-						lrgba.push(srgb_to_linear_d8(v));
+					}
+				},
+			ImageType::LMono =>
+				for v in data {
+					lrgba.push(v);
+					lrgba.push(v);
+					lrgba.push(v);
+					lrgba.push(255);
+				},
+			ImageType::SMono =>
+				for mut v in data {
+					v = srgb_to_linear_d8(v);
+					lrgba.push(v);
+					lrgba.push(v);
+					lrgba.push(v);
+					lrgba.push(255);
+				},
+			ImageType::SRGBA =>
+				for v in data {
+					lrgba.push(srgb_to_linear_d8(v));
+				},
+			ImageType::SRGB =>
+				for v in data {
+					lrgba.push(srgb_to_linear_d8(v));
 
-						if lrgba.len() % 4 == 2 {
-							lrgba.push(255);
-						}
-					},
-				ImageType::Glyph =>
-					for v in data {
-						lrgba.push(0);
-						lrgba.push(0);
-						lrgba.push(0);
-						lrgba.push(srgb_to_linear_d8(v));
-					},
-				ImageType::YUV444 =>
-					for chunk in data.chunks_exact(3) {
-						let mut components = [
-							chunk[0] as f32 + (1.402 * (chunk[2] as f32 - 128.0)),
-							chunk[0] as f32 + (0.344 * (chunk[1] as f32 - 128.0))
-								- (0.714 * (chunk[2] as f32 - 128.0)),
-							chunk[0] as f32 + (1.772 * (chunk[1] as f32 - 128.0)),
-						];
-
-						for v in &mut components {
-							*v = ((*v + (0.055 * 255.0)) / 1.055).powf(2.4).round();
-
-							if *v > 255.0 {
-								*v = 255.0;
-							} else if *v < 0.0 {
-								*v = 0.0;
-							}
+					if lrgba.len() % 4 == 2 {
+						lrgba.push(255);
+					}
+				},
+			ImageType::Glyph =>
+				for v in data {
+					lrgba.push(0);
+					lrgba.push(0);
+					lrgba.push(0);
+					lrgba.push(srgb_to_linear_d8(v));
+				},
+			ImageType::YUV444 =>
+				for chunk in data.chunks_exact(3) {
+					let mut components = [
+						chunk[0] as f32 + (1.402 * (chunk[2] as f32 - 128.0)),
+						chunk[0] as f32 + (0.344 * (chunk[1] as f32 - 128.0))
+							- (0.714 * (chunk[2] as f32 - 128.0)),
+						chunk[0] as f32 + (1.772 * (chunk[1] as f32 - 128.0)),
+					];
+
+					for v in &mut components {
+						*v = ((*v + (0.055 * 255.0)) / 1.055).powf(2.4).round();
+
+						if *v > 255.0 {
+							*v = 255.0;
+						} else if *v < 0.0 {
+							*v = 0.0;
 						}
+					}
 
-						for v in &components {
-							lrgba.push(*v as u8);
-						}
+					for v in &components {
+						lrgba.push(*v as u8);
+					}
 
-						lrgba.push(255);
-					},
-			}
+					lrgba.push(255);
+				},
+		}
+
+		Image {
+			ty: ImageType::LRGBA,
+			dims: self.dims,
+			data: ImageData::D8(lrgba),
+		}
+	}
+
+	/// Convert the image into the form it is stored in within the atlas.
+	fn to_atlas_format(self) -> Self {
+		match &self.data {
+			ImageData::D8(_) => self.to_srgba(),
+			_ => {
+				let image = self.to_lrgba();
+
+				let data = match image.data {
+					ImageData::F32(data) =>
+						ImageData::D16(data.into_iter().map(f32_to_d16).collect()),
+					data => data,
+				};
+
+				Image {
+					ty: image.ty,
+					dims: image.dims,
+					data,
+				}
+			},
+		}
+	}
+
+	fn atlas_format(&self) -> AtlasFormat {
+		match &self.data {
+			ImageData::D8(_) => AtlasFormat::SRGBA8,
+			_ => AtlasFormat::LRGBA16,
+		}
+	}
+
+	/// Produce an image of half the size using a box filter. Components are averaged in
+	/// linear space weighted by alpha. Expects the image to be in atlas format.
+	fn downscaled(&self) -> Image {
+		let (src_w, src_h) = (self.dims.w as usize, self.dims.h as usize);
+		let (w, h) = ((src_w / 2).max(1), (src_h / 2).max(1));
+
+		let linear: Vec<f32> = match &self.data {
+			ImageData::D8(data) =>
+				data.chunks_exact(4)
+					.flat_map(|c| {
+						vec![
+							srgb_to_linear_f32(c[0] as f32 / 255.0),
+							srgb_to_linear_f32(c[1] as f32 / 255.0),
+							srgb_to_linear_f32(c[2] as f32 / 255.0),
+							c[3] as f32 / 255.0,
+						]
+					})
+					.collect(),
+			ImageData::D16(data) => data.iter().map(|v| d16_to_f32(*v)).collect(),
+			ImageData::F32(data) => data.clone(),
+			ImageData::__Nonexhaustive => unreachable!(),
+		};
+
+		let mut out = Vec::with_capacity(w * h * 4);
+
+		for y in 0..h {
+			for x in 0..w {
+				let mut sum = [0.0; 4];
+				let mut count = 0.0;
+
+				for sy in (y * 2)..((y * 2) + 2).min(src_h) {
+					for sx in (x * 2)..((x * 2) + 2).min(src_w) {
+						let i = ((sy * src_w) + sx) * 4;
+						let a = linear[i + 3];
+						sum[0] += linear[i] * a;
+						sum[1] += linear[i + 1] * a;
+						sum[2] += linear[i + 2] * a;
+						sum[3] += a;
+						count += 1.0;
+					}
+				}
 
-			Image {
-				ty: ImageType::LRGBA,
-				dims: self.dims,
-				data: ImageData::D8(lrgba),
+				if sum[3] > 0.0 {
+					out.extend_from_slice(&[
+						sum[0] / sum[3],
+						sum[1] / sum[3],
+						sum[2] / sum[3],
+						sum[3] / count,
+					]);
+				} else {
+					out.extend_from_slice(&[0.0; 4]);
+				}
 			}
-		} else {
-			unreachable!()
+		}
+
+		let data = match &self.data {
+			ImageData::D8(_) =>
+				ImageData::D8(
+					out.chunks_exact(4)
+						.flat_map(|c| {
+							vec![
+								(linear_to_srgb_f32(c[0]) * 255.0).round() as u8,
+								(linear_to_srgb_f32(c[1]) * 255.0).round() as u8,
+								(linear_to_srgb_f32(c[2]) * 255.0).round() as u8,
+								(c[3] * 255.0).round() as u8,
+							]
+						})
+						.collect(),
+				),
+			ImageData::D16(_) => ImageData::D16(out.into_iter().map(f32_to_d16).collect()),
+			ImageData::F32(_) => ImageData::F32(out),
+			ImageData::__Nonexhaustive => unreachable!(),
+		};
+
+		Image {
+			ty: self.ty,
+			dims: ImageDims {
+				w: w as u32,
+				h: h as u32,
+			},
+			data,
+		}
+	}
+
+	/// Size of the image data in bytes.
+	fn data_size(&self) -> usize {
+		match &self.data {
+			ImageData::D8(data) => data.len(),
+			ImageData::D16(data) => data.len() * 2,
+			ImageData::F32(data) => data.len() * 4,
+			ImageData::__Nonexhaustive => 0,
+		}
+	}
+}
+
+/// Format of the images backing an `AtlasImage`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum AtlasFormat {
+	/// 8-bit SRGBA, used for `ImageData::D8`.
+	SRGBA8,
+	/// 16-bit LRGBA, used for `ImageData::D16` and `ImageData::F32`.
+	LRGBA16,
+}
+
+impl AtlasFormat {
+	fn vk_format(&self) -> vulkano::format::Format {
+		match self {
+			AtlasFormat::SRGBA8 => vulkano::format::Format::A8B8G8R8SrgbPack32,
+			AtlasFormat::LRGBA16 => vulkano::format::Format::R16G16B16A16Unorm,
+		}
+	}
+
+	fn pixel_size(&self) -> u32 {
+		match self {
+			AtlasFormat::SRGBA8 => 4,
+			AtlasFormat::LRGBA16 => 8,
 		}
 	}
 }
@@ -369,23 +979,103 @@ enum Command {
 	BatchCacheIDLookup(Arc<CommandResponse<Vec<Option<Coords>>>>, Vec<SubImageCacheID>),
 	Delete(SubImageID),
 	DeleteCache(SubImageCacheID),
+	Compact,
+	Stats(Arc<CommandResponse<AtlasStats>>),
+	SubImageData(Arc<CommandResponse<Option<Image>>>, SubImageID),
 }
 
-struct CommandResponse<T> {
-	response: Mutex<Option<T>>,
-	condvar: Condvar,
+/// Statistics of the atlas obtained from `Atlas::stats()`.
+#[derive(Debug, Clone)]
+pub struct AtlasStats {
+	/// Statistics of each atlas image.
+	pub images: Vec<AtlasImageStats>,
+	/// Amount of cache ids that currently point to a sub image.
+	pub cached_ids: usize,
+	/// Amount of sub images uploaded since the atlas was created.
+	pub uploads: u64,
+	/// Amount of sub images deleted since the atlas was created.
+	pub deletes: u64,
+	/// Amount of times the atlas has been compacted.
+	pub compactions: u64,
+	/// Amount of times the atlas has submitted changes to the gpu.
+	pub updates: u64,
+	/// When the last update was completed along with how long it took.
+	pub last_update: Option<(Instant, Duration)>,
 }
 
-impl<T> CommandResponse<T> {
-	fn new() -> Arc<Self> {
-		Arc::new(CommandResponse {
-			response: Mutex::new(None),
-			condvar: Condvar::new(),
-		})
+impl AtlasStats {
+	/// Total amount of sub images across all atlas images.
+	pub fn sub_images(&self) -> usize {
+		self.images.iter().map(|image| image.sub_images).sum()
 	}
 
-	fn respond(&self, val: T) {
-		*self.response.lock() = Some(val);
+	/// Total amount of gpu memory in bytes used by all atlas images.
+	pub fn gpu_bytes(&self) -> usize {
+		self.images.iter().map(|image| image.gpu_bytes).sum()
+	}
+
+	/// Total amount of memory in bytes used by the retained sub image data.
+	pub fn data_bytes(&self) -> usize {
+		self.images.iter().map(|image| image.data_bytes).sum()
+	}
+}
+
+/// Statistics of a single atlas image. Space within an atlas image is allocated in cells.
+#[derive(Debug, Clone)]
+pub struct AtlasImageStats {
+	pub img_id: AtlasImageID,
+	/// Amount of sub images contained within this image.
+	pub sub_images: usize,
+	/// Amount of cells used by sub images.
+	pub cells_used: usize,
+	/// Amount of cells within the area spanned by sub images.
+	pub cells_spanned: usize,
+	/// Maximum amount of cells this image can hold.
+	pub cells_total: usize,
+	/// Current width of the gpu image.
+	pub width: u32,
+	/// Current height of the gpu image.
+	pub height: u32,
+	/// Amount of gpu images used. Multiple are used so that one can be updated while another
+	/// is being drawn.
+	pub gpu_images: usize,
+	/// Amount of gpu memory in bytes used by this image.
+	pub gpu_bytes: usize,
+	/// Amount of memory in bytes used by the retained sub image data.
+	pub data_bytes: usize,
+}
+
+impl AtlasImageStats {
+	/// Ratio of the maximum amount of cells that are in use.
+	pub fn occupancy(&self) -> f32 {
+		self.cells_used as f32 / self.cells_total as f32
+	}
+
+	/// Ratio of unused cells within the area spanned by sub images.
+	pub fn fragmentation(&self) -> f32 {
+		if self.cells_spanned == 0 {
+			0.0
+		} else {
+			1.0 - (self.cells_used as f32 / self.cells_spanned as f32)
+		}
+	}
+}
+
+struct CommandResponse<T> {
+	response: Mutex<Option<T>>,
+	condvar: Condvar,
+}
+
+impl<T> CommandResponse<T> {
+	fn new() -> Arc<Self> {
+		Arc::new(CommandResponse {
+			response: Mutex::new(None),
+			condvar: Condvar::new(),
+		})
+	}
+
+	fn respond(&self, val: T) {
+		*self.response.lock() = Some(val);
 		self.condvar.notify_one();
 	}
 
@@ -400,13 +1090,165 @@ impl<T> CommandResponse<T> {
 	}
 }
 
+/// Handle to an image being loaded asynchronously into the atlas. The handle may be cloned
+/// and polled from multiple places.
+#[derive(Clone)]
+pub struct AtlasLoadHandle {
+	state: Arc<AtlasLoadState>,
+}
+
+struct AtlasLoadState {
+	result: Mutex<Option<Result<Coords, String>>>,
+	on_complete: Mutex<Vec<Box<dyn FnOnce(Result<Coords, String>) + Send>>>,
+	condvar: Condvar,
+}
+
+impl AtlasLoadHandle {
+	fn new() -> Self {
+		AtlasLoadHandle {
+			state: Arc::new(AtlasLoadState {
+				result: Mutex::new(None),
+				on_complete: Mutex::new(Vec::new()),
+				condvar: Condvar::new(),
+			}),
+		}
+	}
+
+	fn complete(&self, res: Result<Coords, String>) {
+		let mut result = self.state.result.lock();
+		*result = Some(res.clone());
+		let funcs = self.state.on_complete.lock().split_off(0);
+		drop(result);
+		self.state.condvar.notify_all();
+
+		for func in funcs {
+			func(res.clone());
+		}
+	}
+
+	/// Returns true if the load has completed, successfully or not.
+	pub fn is_ready(&self) -> bool {
+		self.state.result.lock().is_some()
+	}
+
+	/// Obtain the result if the load has completed without blocking.
+	pub fn poll(&self) -> Option<Result<Coords, String>> {
+		self.state.result.lock().clone()
+	}
+
+	/// Block the current thread until the load has completed.
+	pub fn wait(&self) -> Result<Coords, String> {
+		let mut result = self.state.result.lock();
+
+		while result.is_none() {
+			self.state.condvar.wait(&mut result);
+		}
+
+		result.clone().unwrap()
+	}
+
+	/// Call the provided function once the load has completed. This will be called from the
+	/// atlas's load thread or, if the load has already completed, the current thread.
+	pub fn on_complete<F: FnOnce(Result<Coords, String>) + Send + 'static>(&self, func: F) {
+		let result = self.state.result.lock();
+
+		match result.as_ref() {
+			Some(res) => {
+				let res = res.clone();
+				drop(result);
+				func(res);
+			},
+			None => self.state.on_complete.lock().push(Box::new(func)),
+		}
+	}
+}
+
+/// Frames of an animated image loaded into the atlas.
+#[derive(Debug, Clone)]
+pub struct AtlasAnimation {
+	frames: Vec<AtlasFrame>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasFrame {
+	pub coords: Coords,
+	/// How long this frame is shown before the next.
+	pub delay: Duration,
+}
+
+impl AtlasAnimation {
+	pub fn frames(&self) -> &[AtlasFrame] {
+		&self.frames
+	}
+
+	/// Returns true if there is more than one frame.
+	pub fn is_animated(&self) -> bool {
+		self.frames.len() > 1
+	}
+
+	/// Total duration of a single loop of the animation.
+	pub fn duration(&self) -> Duration {
+		self.frames.iter().map(|frame| frame.delay).sum()
+	}
+
+	/// Obtain the index of the frame shown at `elapsed` into the animation along with the time
+	/// remaining until the next frame is shown. The time remaining is `None` once the final
+	/// frame has been reached and `looping` is false.
+	pub fn frame_at(&self, elapsed: Duration, looping: bool) -> (usize, Option<Duration>) {
+		let duration = self.duration();
+
+		if !self.is_animated() || duration == Duration::from_secs(0) {
+			return (0, None);
+		}
+
+		let mut elapsed = if looping {
+			Duration::from_nanos((elapsed.as_nanos() % duration.as_nanos()) as u64)
+		} else if elapsed >= duration {
+			return (self.frames.len() - 1, None);
+		} else {
+			elapsed
+		};
+
+		for (i, frame) in self.frames.iter().enumerate() {
+			if elapsed < frame.delay {
+				if !looping && i == self.frames.len() - 1 {
+					return (i, None);
+				}
+
+				return (i, Some(frame.delay - elapsed));
+			}
+
+			elapsed -= frame.delay;
+		}
+
+		(self.frames.len() - 1, None)
+	}
+}
+
 pub struct Atlas {
 	basalt: Arc<Basalt>,
 	cmd_queue: Injector<Command>,
+	/// Started on the first asynchronous load.
+	load_queue: Mutex<Option<Sender<Box<dyn FnOnce() + Send>>>>,
 	empty_image: Arc<BstImageView>,
 	default_sampler: Arc<Sampler>,
 	unparker: Unparker,
 	image_views: Mutex<Option<(Instant, Arc<HashMap<AtlasImageID, Arc<BstImageView>>>)>>,
+	/// Image views from before a compaction keyed by the generation of coords they match.
+	/// Kept alive until released, so coords from before the compaction can still be drawn.
+	retained_views:
+		Mutex<BTreeMap<u64, (Instant, Arc<HashMap<AtlasImageID, Arc<BstImageView>>>)>>,
+	generation: AtomicU64,
+	/// Coords moved by compactions keyed by the image and sub image ids of the coords before
+	/// the move. Values are the coords before and after.
+	relocated: Mutex<HashMap<(AtlasImageID, SubImageID), (Coords, Coords)>>,
+	/// Keys of `relocated` moved by each compaction keyed by the generation it produced.
+	relocations: Mutex<BTreeMap<u64, Arc<HashSet<(AtlasImageID, SubImageID)>>>>,
+	compact_threshold: Mutex<Option<f32>>,
+	animations: Mutex<HashMap<SubImageCacheID, Arc<AtlasAnimation>>>,
+	levels: Mutex<HashMap<SubImageID, Arc<Vec<Coords>>>>,
+	/// Levels being generated on the load threads.
+	pending_levels: Mutex<HashMap<SubImageID, AtlasLoadHandle>>,
 }
 
 impl Atlas {
@@ -442,14 +1284,22 @@ impl Atlas {
 
 		let parker = Parker::new();
 		let unparker = parker.unparker().clone();
-
 		let atlas_ret = Arc::new(Atlas {
 			basalt,
 			unparker,
+			load_queue: Mutex::new(None),
 			default_sampler,
 			empty_image,
 			cmd_queue: Injector::new(),
 			image_views: Mutex::new(None),
+			retained_views: Mutex::new(BTreeMap::new()),
+			generation: AtomicU64::new(0),
+			relocated: Mutex::new(HashMap::new()),
+			relocations: Mutex::new(BTreeMap::new()),
+			compact_threshold: Mutex::new(Some(DEFAULT_COMPACT_THRESHOLD)),
+			animations: Mutex::new(HashMap::new()),
+			levels: Mutex::new(HashMap::new()),
+			pending_levels: Mutex::new(HashMap::new()),
 		});
 
 		let atlas = atlas_ret.clone();
@@ -460,11 +1310,20 @@ impl Atlas {
 			let mut sub_img_id_count = 1;
 			let mut cached_map = HashMap::new();
 			let mut execute = false;
+			let mut relocated_keys = HashSet::new();
+			let mut compacted_frag = 0.0;
+			let mut upload_count = 0;
+			let mut delete_count = 0;
+			let mut compact_count = 0;
+			let mut update_count = 0;
+			let mut last_update = None;
 
 			loop {
 				iter_start = Instant::now();
 				let mut cmds = Vec::new();
 				let mut got_cmd = false;
+				let mut deleted = false;
+				let mut compact = None;
 
 				loop {
 					let cmd = match atlas.cmd_queue.steal() {
@@ -477,9 +1336,14 @@ impl Atlas {
 
 					match cmd {
 						Command::Upload(response, cache_id, up_image) => {
+							let format = up_image.atlas_format();
 							let mut space_op = None;
 
 							for (i, atlas_image) in atlas_images.iter().enumerate() {
+								if atlas_image.format != format {
+									continue;
+								}
+
 								if let Some(region) = atlas_image.find_space_for(&up_image.dims)
 								{
 									space_op = Some((i + 1, region));
@@ -488,7 +1352,7 @@ impl Atlas {
 							}
 
 							if space_op.is_none() {
-								let atlas_image = AtlasImage::new(atlas.basalt.clone());
+								let atlas_image = AtlasImage::new(atlas.basalt.clone(), format);
 
 								match atlas_image.find_space_for(&up_image.dims) {
 									Some(region) => {
@@ -517,9 +1381,49 @@ impl Atlas {
 							}
 
 							response.respond(Ok(coords));
+							upload_count += 1;
 
 							atlas_images[atlas_image_i - 1]
-								.insert(&region, sub_img_id, coords, up_image);
+								.insert(&region, sub_img_id, coords, up_image, None);
+						},
+						// Deletes are handled in order with uploads so that a delete followed
+						// by an upload of the same cache id doesn't remove the new image.
+						Command::Delete(sub_img_id) => {
+							deleted = true;
+							atlas.relocated.lock().retain(|(_, id), _| *id != sub_img_id);
+							cached_map.retain(|_, coords| coords.sub_img_id != sub_img_id);
+
+							for atlas_image in &mut atlas_images {
+								if atlas_image.remove(sub_img_id) {
+									delete_count += 1;
+									break;
+								}
+							}
+
+							delete_count += atlas.remove_levels(&mut atlas_images, sub_img_id);
+						},
+						Command::DeleteCache(cache_id) => {
+							if let Some(coords) = cached_map.remove(&cache_id) {
+								deleted = true;
+								atlas
+									.relocated
+									.lock()
+									.retain(|(_, id), _| *id != coords.sub_img_id);
+
+								if let Some(atlas_image) =
+									atlas_images.get_mut(coords.img_id as usize - 1)
+								{
+									if atlas_image.remove(coords.sub_img_id) {
+										delete_count += 1;
+									}
+								}
+
+								delete_count +=
+									atlas.remove_levels(&mut atlas_images, coords.sub_img_id);
+							}
+						},
+						Command::Compact => {
+							compact = Some(0.0);
 						},
 						c => cmds.push(c),
 					}
@@ -530,11 +1434,56 @@ impl Atlas {
 					continue;
 				}
 
+				if compact.is_none() && deleted {
+					if let Some(threshold) = *atlas.compact_threshold.lock() {
+						// Packing isn't perfect, so only compact again once fragmentation is
+						// halfway between what the last compaction achieved and fully unused.
+						// This prevents compacting after every delete.
+						if AtlasImage::fragmentation(&atlas_images)
+							> threshold.max((1.0 + compacted_frag) / 2.0)
+						{
+							compact = Some(threshold);
+						}
+					}
+				}
+
+				if let Some(threshold) = compact {
+					let moved =
+						AtlasImage::compact(atlas.basalt.clone(), &mut atlas_images, threshold);
+					compacted_frag = AtlasImage::fragmentation(&atlas_images);
+					compact_count += 1;
+
+					for coords in cached_map.values_mut() {
+						if let Some((_, new_coords)) =
+							moved.get(&(coords.img_id, coords.sub_img_id))
+						{
+							*coords = *new_coords;
+						}
+					}
+
+					let mut relocated = atlas.relocated.lock();
+
+					// Coords from before earlier compactions are moved along to the new coords.
+					for (_, new) in relocated.values_mut() {
+						if let Some((old_coords, new_coords)) =
+							moved.get(&(new.img_id, new.sub_img_id))
+						{
+							if old_coords == new {
+								*new = *new_coords;
+							}
+						}
+					}
+
+					relocated_keys.extend(moved.keys().cloned());
+					relocated.extend(moved.into_iter());
+				}
+
 				for cmd in cmds {
 					match cmd {
-						Command::Upload(..) => unreachable!(),
-						Command::Delete(_sub_img_id) => (), // TODO: Implement Deletes
-						Command::DeleteCache(_sub_img_cache_id) => (),
+						Command::Upload(..)
+						| Command::Delete(..)
+						| Command::DeleteCache(..)
+						| Command::Compact => unreachable!(),
 						Command::CacheIDLookup(response, cache_id) => {
 							response.respond(cached_map.get(&cache_id).cloned());
 						},
@@ -546,6 +1495,29 @@ impl Atlas {
 									.collect(),
 							);
 						},
+						Command::SubImageData(response, sub_img_id) => {
+							response.respond(atlas_images.iter().find_map(|atlas_image| {
+								atlas_image
+									.sub_imgs
+									.get(&sub_img_id)
+									.map(|sub_img| sub_img.img.clone())
+							}));
+						},
+						Command::Stats(response) => {
+							response.respond(AtlasStats {
+								images: atlas_images
+									.iter()
+									.enumerate()
+									.map(|(i, atlas_image)| atlas_image.stats((i + 1) as u64))
+									.collect(),
+								cached_ids: cached_map.len(),
+								uploads: upload_count,
+								deletes: delete_count,
+								compactions: compact_count,
+								updates: update_count,
+								last_update,
+							});
+						},
 					}
 				}
 
@@ -580,6 +1552,12 @@ impl Atlas {
                             .then_signal_fence_and_flush()
                             .unwrap(),
 					);
+
+					update_count += 1;
+					last_update = Some((Instant::now(), iter_start.elapsed()));
+				}
+
+				if execute || !relocated_keys.is_empty() {
 					let mut draw_map = HashMap::new();
 
 					for (i, atlas_image) in atlas_images.iter_mut().enumerate() {
@@ -588,126 +1566,527 @@ impl Atlas {
 						}
 					}
 
-					*atlas.image_views.lock() = Some((Instant::now(), Arc::new(draw_map)));
-				}
+					let mut image_views = atlas.image_views.lock();
+
+					// Coords from before a compaction are still drawn until their holders
+					// notice the new generation, so the previous views are kept alive.
+					if !relocated_keys.is_empty() {
+						if let Some(previous) = image_views.take() {
+							atlas.retained_views.lock().insert(
+								atlas.generation.load(atomic::Ordering::SeqCst),
+								previous,
+							);
+						}
+
+						let generation = atlas.generation.load(atomic::Ordering::SeqCst) + 1;
+
+						atlas.relocations.lock().insert(
+							generation,
+							Arc::new(::std::mem::replace(&mut relocated_keys, HashSet::new())),
+						);
+
+						atlas.generation.store(generation, atomic::Ordering::SeqCst);
+					}
+
+					*image_views = Some((Instant::now(), Arc::new(draw_map)));
+				}
+
+				if PRINT_UPDATE_TIME && execute {
+					let mut out = format!(
+						"Atlas Updated in {:.1} ms. ",
+						iter_start.elapsed().as_micros() as f64 / 1000.0
+					);
+
+					for (i, (w, h)) in sizes.into_iter().enumerate() {
+						out.push_str(format!("{}:{}x{} ", i + 1, w, h).as_str());
+					}
+
+					out.pop();
+					println!("{}", out);
+				}
+			}
+		});
+
+		atlas_ret
+	}
+
+	/// Obtain the current image views for the Atlas images. These should be used direclty when
+	/// drawing and dropped afterwards. They should proably shouldn't be stored. Keeping these
+	/// views alive will result in the Atlas consuming more resources or even preventing it from
+	/// updating at all in a bad case.
+	pub fn image_views(
+		&self,
+	) -> Option<(Instant, Arc<HashMap<AtlasImageID, Arc<BstImageView>>>)> {
+		self.image_views.lock().clone()
+	}
+
+	/// Like `image_views()`, but the views returned match coords of the provided generation.
+	/// If sub images have since been moved by a compaction, the views from before it are
+	/// returned until released with `release_image_views()`.
+	pub fn image_views_for(
+		&self,
+		generation: u64,
+	) -> Option<(Instant, Arc<HashMap<AtlasImageID, Arc<BstImageView>>>)> {
+		let image_views = self.image_views.lock();
+
+		match self.retained_views.lock().get(&generation) {
+			Some(retained) => Some(retained.clone()),
+			None => image_views.clone(),
+		}
+	}
+
+	/// Release the views retained for generations before the provided one. Should be called
+	/// once nothing is drawn with coords from those generations. The sub images moved up to
+	/// and including the provided generation are no longer returned by `relocated_since()`.
+	pub fn release_image_views(&self, generation: u64) {
+		self.retained_views.lock().retain(|retained, _| *retained >= generation);
+		self.relocations.lock().retain(|relocation, _| *relocation > generation);
+	}
+
+	/// The generation of coords. This is incremented each time a compaction moves sub images.
+	/// Once it changes, coords obtained before should be updated with `relocated_coords()`.
+	pub fn generation(&self) -> u64 {
+		self.generation.load(atomic::Ordering::SeqCst)
+	}
+
+	/// The sub images moved by compactions after the provided generation as the `img_id` and
+	/// `sub_img_id` of their coords before the move. Coords whose ids are not included are
+	/// still current.
+	pub fn relocated_since(&self, generation: u64) -> HashSet<(AtlasImageID, SubImageID)> {
+		self.relocations
+			.lock()
+			.range((generation + 1)..)
+			.flat_map(|(_, keys)| keys.iter().cloned())
+			.collect()
+	}
+
+	/// General purpose empty image that can be used in descritors where an image is required,
+	/// but where it won't be used.
+	pub fn empty_image(&self) -> Arc<BstImageView> {
+		self.empty_image.clone()
+	}
+
+	/// The recommended default sampler for when using atlas image views. May be used elsewhere,
+	/// but probably more ideal to create your own.
+	pub fn default_sampler(&self) -> Arc<Sampler> {
+		self.default_sampler.clone()
+	}
+
+	/// Remove a sub image. The space it occupied will be cleared and made available to
+	/// future uploads. Any cache id pointing to this sub image will also be removed.
+	pub fn delete_sub_image(&self, sub_img_id: SubImageID) {
+		self.cmd_queue.push(Command::Delete(sub_img_id));
+		self.unparker.unpark();
+	}
+
+	/// Remove a sub image by its cache id. The space it occupied will be cleared and made
+	/// available to future uploads.
+	pub fn delete_sub_cache_image(&self, sub_img_cache_id: SubImageCacheID) {
+		if let Some(animation) = self.animations.lock().remove(&sub_img_cache_id) {
+			for i in 1..animation.frames.len() {
+				self.cmd_queue.push(Command::DeleteCache(SubImageCacheID::Frame(
+					Box::new(sub_img_cache_id.clone()),
+					i,
+				)));
+			}
+		}
+
+		self.cmd_queue.push(Command::DeleteCache(sub_img_cache_id));
+		self.unparker.unpark();
+	}
+
+	/// Repack the sub images of every fragmented atlas image. Sub images are copied on the
+	/// gpu to their new location, and their coords will change. Bins using them will be
+	/// updated automatically. Others holding onto coords should watch `generation()` and use
+	/// `relocated_coords()` to obtain the current coords.
+	pub fn compact(&self) {
+		self.cmd_queue.push(Command::Compact);
+		self.unparker.unpark();
+	}
+
+	/// Set the fragmentation ratio at which the atlas will automatically compact itself after
+	/// sub images are deleted. The ratio is the unused portion of the space spanned by sub
+	/// images. Only atlas images above the ratio are repacked. Setting this to `None`
+	/// disables automatic compaction. Defaults to `0.5`.
+	pub fn set_compact_threshold(&self, threshold: Option<f32>) {
+		*self.compact_threshold.lock() = threshold;
+	}
+
+	/// Obtain the current coords for a sub image. If the sub image has been moved by a
+	/// compaction the new coords will be returned; otherwise, the provided coords are returned.
+	pub fn relocated_coords(&self, coords: Coords) -> Coords {
+		match self.relocated.lock().get(&(coords.img_id, coords.sub_img_id)) {
+			Some((old, new)) if *old == coords => *new,
+			_ => coords,
+		}
+	}
+
+	/// Generate pre-filtered lower resolution levels for a sub image. Each level is half the
+	/// size of the previous down to 16 pixels. This is done once per sub image and the
+	/// levels are deleted along with the sub image. See `level_coords()`.
+	pub fn generate_levels(&self, coords: Coords) -> Result<(), String> {
+		if self.levels.lock().contains_key(&coords.sub_img_id) {
+			return Ok(());
+		}
+
+		let response = CommandResponse::new();
+		self.cmd_queue.push(Command::SubImageData(response.clone(), coords.sub_img_id));
+		self.unparker.unpark();
+
+		let mut image = match response.wait_for_response() {
+			Some(some) => some,
+			None => return Err(format!("Sub image {} doesn't exist.", coords.sub_img_id)),
+		};
+
+		let mut responses = Vec::new();
+
+		while image.dims.w.max(image.dims.h) > MIN_LEVEL_SIZE {
+			image = image.downscaled();
+			let response = CommandResponse::new();
+			self.cmd_queue.push(Command::Upload(
+				response.clone(),
+				SubImageCacheID::None,
+				image.clone(),
+			));
+			responses.push(response);
+		}
+
+		self.unparker.unpark();
+		let mut levels = Vec::with_capacity(responses.len());
+
+		for response in responses {
+			levels.push(response.wait_for_response()?);
+		}
+
+		self.levels.lock().insert(coords.sub_img_id, Arc::new(levels));
+		Ok(())
+	}
+
+	/// Same as `generate_levels()`, but the levels are generated on the atlas's load threads.
+	/// The handle completes with the provided coords once `level_coords()` uses the levels.
+	/// Requests for a sub image whose levels are already being generated share a handle.
+	pub fn generate_levels_async(self: &Arc<Self>, coords: Coords) -> AtlasLoadHandle {
+		let mut pending = self.pending_levels.lock();
+
+		if let Some(handle) = pending.get(&coords.sub_img_id) {
+			return handle.clone();
+		}
+
+		let handle = AtlasLoadHandle::new();
+
+		if self.levels.lock().contains_key(&coords.sub_img_id) {
+			handle.complete(Ok(coords));
+			return handle;
+		}
+
+		pending.insert(coords.sub_img_id, handle.clone());
+		drop(pending);
+		let handle_cp = handle.clone();
+		let atlas = self.clone();
+
+		self.queue_load(Box::new(move || {
+			let result = atlas.generate_levels(coords).map(|_| coords);
+			atlas.pending_levels.lock().remove(&coords.sub_img_id);
+			handle_cp.complete(result);
+		}));
+
+		handle
+	}
+
+	/// Obtain the coords of the smallest level of a sub image that is still at least `w` by
+	/// `h` in size. If levels haven't been generated with `generate_levels()` the provided
+	/// coords are returned. Like `relocated_coords()` the current coords are returned.
+	pub fn level_coords(&self, coords: Coords, w: f32, h: f32) -> Coords {
+		let level = match self.levels.lock().get(&coords.sub_img_id) {
+			Some(levels) =>
+				levels
+					.iter()
+					.take_while(|level| level.w as f32 >= w && level.h as f32 >= h)
+					.last()
+					.cloned()
+					.unwrap_or(coords),
+			None => coords,
+		};
+
+		self.relocated_coords(level)
+	}
+
+	/// Obtain statistics about the current state of the atlas. The statistics reflect the
+	/// last update made by the atlas and not any pending changes.
+	pub fn stats(&self) -> AtlasStats {
+		let response = CommandResponse::new();
+		self.cmd_queue.push(Command::Stats(response.clone()));
+		self.unparker.unpark();
+		response.wait_for_response()
+	}
+
+	/// Obtain coords given a cache id. If doing this in bulk there will be a considerable
+	/// performance improvement when using `batch_cache_coords()`.
+	pub fn cache_coords(&self, cache_id: SubImageCacheID) -> Option<Coords> {
+		let response = CommandResponse::new();
+		self.cmd_queue.push(Command::CacheIDLookup(response.clone(), cache_id));
+		self.unparker.unpark();
+		response.wait_for_response()
+	}
+
+	/// Obtain coords for a set of cache ids. This method will be a considerable
+	/// improvment for obtaining coords over `cache_coords` where this is done in bulk.
+	pub fn batch_cache_coords(&self, cache_ids: Vec<SubImageCacheID>) -> Vec<Option<Coords>> {
+		let response = CommandResponse::new();
+		self.cmd_queue.push(Command::BatchCacheIDLookup(response.clone(), cache_ids));
+		self.unparker.unpark();
+		response.wait_for_response()
+	}
+
+	pub fn load_image(
+		&self,
+		cache_id: SubImageCacheID,
+		image: Image,
+	) -> Result<Coords, String> {
+		let response = CommandResponse::new();
+		self.cmd_queue.push(Command::Upload(
+			response.clone(),
+			cache_id,
+			image.to_atlas_format(),
+		));
+		self.unparker.unpark();
+		response.wait_for_response()
+	}
+
+	/// Load an image from encoded bytes. The color space is detected from the metadata embedded
+	/// within the image. See `Image::from_bytes()` for details.
+	pub fn load_image_from_bytes(
+		&self,
+		cache_id: SubImageCacheID,
+		bytes: Vec<u8>,
+	) -> Result<Coords, String> {
+		let image = Image::from_bytes(bytes.as_slice(), None)?;
+		self.load_image(cache_id, image)
+	}
+
+	/// Same as `load_image_from_bytes()`, but the color space of the image is provided by the
+	/// caller instead of being detected.
+	pub fn load_image_from_bytes_as(
+		&self,
+		cache_id: SubImageCacheID,
+		bytes: Vec<u8>,
+		color_space: ImageColorSpace,
+	) -> Result<Coords, String> {
+		let image = Image::from_bytes(bytes.as_slice(), Some(color_space))?;
+		self.load_image(cache_id, image)
+	}
+
+	pub fn load_image_from_path<P: Into<PathBuf>>(&self, path: P) -> Result<Coords, String> {
+		let path_buf = path.into();
+		let cache_id = SubImageCacheID::Path(path_buf.clone());
+
+		if let Some(coords) = self.cache_coords(cache_id.clone()) {
+			return Ok(coords);
+		}
+
+		let mut handle = match File::open(path_buf) {
+			Ok(ok) => ok,
+			Err(e) => return Err(format!("Failed to open file: {}", e)),
+		};
+
+		let mut bytes = Vec::new();
+
+		if let Err(e) = handle.read_to_end(&mut bytes) {
+			return Err(format!("Failed to read file: {}", e));
+		}
+
+		self.load_image_from_bytes(cache_id, bytes)
+	}
+
+	pub fn load_image_from_url<U: AsRef<str>>(
+		self: &Arc<Self>,
+		url: U,
+	) -> Result<Coords, String> {
+		let cache_id = SubImageCacheID::Url(url.as_ref().to_string());
+
+		if let Some(coords) = self.cache_coords(cache_id.clone()) {
+			return Ok(coords);
+		}
+
+		let bytes = match self.basalt.fetcher().fetch(url.as_ref()) {
+			Ok(ok) => ok,
+			Err(e) => return Err(format!("Failed to retreive url data: {}", e)),
+		};
+
+		self.load_image_from_bytes(cache_id, bytes)
+	}
+
+	/// Load all the frames of an animated GIF or APNG into the atlas. Images that aren't
+	/// animated result in a single frame. See `Image::frames_from_bytes()` for details.
+	pub fn load_animation_from_bytes(
+		&self,
+		cache_id: SubImageCacheID,
+		bytes: Vec<u8>,
+	) -> Result<Arc<AtlasAnimation>, String> {
+		if let Some(animation) = self.animations.lock().get(&cache_id) {
+			return Ok(animation.clone());
+		}
+
+		let images = Image::frames_from_bytes(bytes.as_slice(), None)?;
+
+		let frame_cache_ids: Vec<_> = (0..images.len())
+			.map(|i| {
+				match cache_id {
+					SubImageCacheID::None => SubImageCacheID::None,
+					_ if i == 0 => cache_id.clone(),
+					_ => SubImageCacheID::Frame(Box::new(cache_id.clone()), i),
+				}
+			})
+			.collect();
+
+		// The first frame may already be loaded by load_image_from_path() and the like.
+		let cached = match cache_id {
+			SubImageCacheID::None => vec![None; images.len()],
+			_ => self.batch_cache_coords(frame_cache_ids.clone()),
+		};
+
+		// Uploads are all queued before waiting so they can be done in a single update.
+		let pending: Vec<_> = images
+			.into_iter()
+			.zip(frame_cache_ids.into_iter().zip(cached.into_iter()))
+			.map(|((image, delay), (frame_cache_id, cached))| {
+				match cached {
+					Some(coords) => (Ok(coords), delay),
+					None => {
+						let response = CommandResponse::new();
+						self.cmd_queue.push(Command::Upload(
+							response.clone(),
+							frame_cache_id,
+							image.to_atlas_format(),
+						));
+						(Err(response), delay)
+					},
+				}
+			})
+			.collect();
+
+		self.unparker.unpark();
+		let mut frames = Vec::with_capacity(pending.len());
+
+		for (coords, delay) in pending {
+			frames.push(AtlasFrame {
+				coords: match coords {
+					Ok(coords) => coords,
+					Err(response) => response.wait_for_response()?,
+				},
+				delay,
+			});
+		}
+
+		let animation = Arc::new(AtlasAnimation {
+			frames,
+		});
+
+		if cache_id != SubImageCacheID::None {
+			self.animations.lock().insert(cache_id, animation.clone());
+		}
+
+		Ok(animation)
+	}
+
+	pub fn load_animation_from_path<P: Into<PathBuf>>(
+		&self,
+		path: P,
+	) -> Result<Arc<AtlasAnimation>, String> {
+		let path_buf = path.into();
+		let cache_id = SubImageCacheID::Path(path_buf.clone());
+
+		if let Some(animation) = self.animations.lock().get(&cache_id) {
+			return Ok(animation.clone());
+		}
 
-				if PRINT_UPDATE_TIME && execute {
-					let mut out = format!(
-						"Atlas Updated in {:.1} ms. ",
-						iter_start.elapsed().as_micros() as f64 / 1000.0
-					);
+		let mut handle = match File::open(path_buf) {
+			Ok(ok) => ok,
+			Err(e) => return Err(format!("Failed to open file: {}", e)),
+		};
 
-					for (i, (w, h)) in sizes.into_iter().enumerate() {
-						out.push_str(format!("{}:{}x{} ", i + 1, w, h).as_str());
-					}
+		let mut bytes = Vec::new();
 
-					out.pop();
-					println!("{}", out);
-				}
-			}
-		});
+		if let Err(e) = handle.read_to_end(&mut bytes) {
+			return Err(format!("Failed to read file: {}", e));
+		}
 
-		atlas_ret
+		self.load_animation_from_bytes(cache_id, bytes)
 	}
 
-	/// Obtain the current image views for the Atlas images. These should be used direclty when
-	/// drawing and dropped afterwards. They should proably shouldn't be stored. Keeping these
-	/// views alive will result in the Atlas consuming more resources or even preventing it from
-	/// updating at all in a bad case.
-	pub fn image_views(
+	pub fn load_animation_from_url<U: AsRef<str>>(
 		&self,
-	) -> Option<(Instant, Arc<HashMap<AtlasImageID, Arc<BstImageView>>>)> {
-		self.image_views.lock().clone()
-	}
+		url: U,
+	) -> Result<Arc<AtlasAnimation>, String> {
+		let cache_id = SubImageCacheID::Url(url.as_ref().to_string());
 
-	/// General purpose empty image that can be used in descritors where an image is required,
-	/// but where it won't be used.
-	pub fn empty_image(&self) -> Arc<BstImageView> {
-		self.empty_image.clone()
-	}
+		if let Some(animation) = self.animations.lock().get(&cache_id) {
+			return Ok(animation.clone());
+		}
 
-	/// The recommended default sampler for when using atlas image views. May be used elsewhere,
-	/// but probably more ideal to create your own.
-	pub fn default_sampler(&self) -> Arc<Sampler> {
-		self.default_sampler.clone()
-	}
+		let bytes = match self.basalt.fetcher().fetch(url.as_ref()) {
+			Ok(ok) => ok,
+			Err(e) => return Err(format!("Failed to retreive url data: {}", e)),
+		};
 
-	/// Remove a sub image. Currently not implemented.
-	pub fn delete_sub_image(&self, sub_img_id: SubImageID) {
-		self.cmd_queue.push(Command::Delete(sub_img_id));
+		self.load_animation_from_bytes(cache_id, bytes)
 	}
 
-	/// Remove a sub cache image. Currently not implemented.
-	pub fn delete_sub_cache_image(&self, sub_img_cache_id: SubImageCacheID) {
-		self.cmd_queue.push(Command::DeleteCache(sub_img_cache_id));
-	}
+	/// Remove the levels generated for a sub image from the atlas images. Returns the amount of
+	/// sub images removed.
+	fn remove_levels(&self, atlas_images: &mut Vec<AtlasImage>, sub_img_id: SubImageID) -> u64 {
+		let levels = match self.levels.lock().remove(&sub_img_id) {
+			Some(some) => some,
+			None => return 0,
+		};
 
-	/// Obtain coords given a cache id. If doing this in bulk there will be a considerable
-	/// performance improvement when using `batch_cache_coords()`.
-	pub fn cache_coords(&self, cache_id: SubImageCacheID) -> Option<Coords> {
-		let response = CommandResponse::new();
-		self.cmd_queue.push(Command::CacheIDLookup(response.clone(), cache_id));
-		self.unparker.unpark();
-		response.wait_for_response()
-	}
+		let mut relocated = self.relocated.lock();
+		let mut removed = 0;
 
-	/// Obtain coords for a set of cache ids. This method will be a considerable
-	/// improvment for obtaining coords over `cache_coords` where this is done in bulk.
-	pub fn batch_cache_coords(&self, cache_ids: Vec<SubImageCacheID>) -> Vec<Option<Coords>> {
-		let response = CommandResponse::new();
-		self.cmd_queue.push(Command::BatchCacheIDLookup(response.clone(), cache_ids));
-		self.unparker.unpark();
-		response.wait_for_response()
-	}
+		for level in levels.iter() {
+			relocated.retain(|(_, id), _| *id != level.sub_img_id);
 
-	pub fn load_image(
-		&self,
-		cache_id: SubImageCacheID,
-		image: Image,
-	) -> Result<Coords, String> {
-		let response = CommandResponse::new();
-		self.cmd_queue.push(Command::Upload(response.clone(), cache_id, image.to_srgba()));
-		self.unparker.unpark();
-		response.wait_for_response()
+			for atlas_image in atlas_images.iter_mut() {
+				if atlas_image.remove(level.sub_img_id) {
+					removed += 1;
+					break;
+				}
+			}
+		}
+
+		removed
 	}
 
-	pub fn load_image_from_bytes(
+	/// Rasterize an SVG to fit within `w` by `h` pixels and load it into the atlas. Each size
+	/// is cached separately under `SubImageCacheID::Sized`. See `Image::from_svg_bytes()`.
+	pub fn load_svg_from_bytes(
 		&self,
 		cache_id: SubImageCacheID,
 		bytes: Vec<u8>,
+		w: u32,
+		h: u32,
 	) -> Result<Coords, String> {
-		let format = match image::guess_format(bytes.as_slice()) {
-			Ok(ok) => ok,
-			Err(e) => return Err(format!("Failed to guess image type for data: {}", e)),
-		};
-
-		let (w, h, data) = match image::load_from_memory(bytes.as_slice()) {
-			Ok(image) => (image.width(), image.height(), image.to_rgba8().into_vec()),
-			Err(e) => return Err(format!("Failed to read image: {}", e)),
-		};
-
-		let image_type = match format {
-			image::ImageFormat::Jpeg => ImageType::SRGBA,
-			_ => ImageType::LRGBA,
+		let cache_id = match cache_id {
+			SubImageCacheID::None => SubImageCacheID::None,
+			cache_id => SubImageCacheID::Sized(Box::new(cache_id), w, h),
 		};
 
-		let image = Image::new(
-			image_type,
-			ImageDims {
-				w,
-				h,
-			},
-			ImageData::D8(data),
-		)
-		.map_err(|e| format!("Invalid Image: {}", e))?;
+		let image = Image::from_svg_bytes(bytes.as_slice(), w, h)?;
 		self.load_image(cache_id, image)
 	}
 
-	pub fn load_image_from_path<P: Into<PathBuf>>(&self, path: P) -> Result<Coords, String> {
+	pub fn load_svg_from_path<P: Into<PathBuf>>(
+		&self,
+		path: P,
+		w: u32,
+		h: u32,
+	) -> Result<Coords, String> {
 		let path_buf = path.into();
 		let cache_id = SubImageCacheID::Path(path_buf.clone());
 
-		if let Some(coords) = self.cache_coords(cache_id.clone()) {
+		if let Some(coords) =
+			self.cache_coords(SubImageCacheID::Sized(Box::new(cache_id.clone()), w, h))
+		{
 			return Ok(coords);
 		}
 
@@ -722,28 +2101,111 @@ impl Atlas {
 			return Err(format!("Failed to read file: {}", e));
 		}
 
-		self.load_image_from_bytes(cache_id, bytes)
+		self.load_svg_from_bytes(cache_id, bytes, w, h)
 	}
 
-	pub fn load_image_from_url<U: AsRef<str>>(
-		self: &Arc<Self>,
+	pub fn load_svg_from_url<U: AsRef<str>>(
+		&self,
 		url: U,
+		w: u32,
+		h: u32,
 	) -> Result<Coords, String> {
 		let cache_id = SubImageCacheID::Url(url.as_ref().to_string());
 
-		if let Some(coords) = self.cache_coords(cache_id.clone()) {
+		if let Some(coords) =
+			self.cache_coords(SubImageCacheID::Sized(Box::new(cache_id.clone()), w, h))
+		{
 			return Ok(coords);
 		}
 
-		let bytes = match misc::http::get_bytes(&url) {
+		let bytes = match self.basalt.fetcher().fetch(url.as_ref()) {
 			Ok(ok) => ok,
 			Err(e) => return Err(format!("Failed to retreive url data: {}", e)),
 		};
 
-		self.load_image_from_bytes(cache_id, bytes)
+		self.load_svg_from_bytes(cache_id, bytes, w, h)
+	}
+
+	fn load_async<F>(self: &Arc<Self>, load: F) -> AtlasLoadHandle
+	where
+		F: FnOnce(&Arc<Self>) -> Result<Coords, String> + Send + 'static,
+	{
+		let handle = AtlasLoadHandle::new();
+		let handle_cp = handle.clone();
+		let atlas = self.clone();
+
+		self.queue_load(Box::new(move || {
+			handle_cp.complete(load(&atlas));
+		}));
+
+		handle
+	}
+
+	/// Run a load on the load threads, starting them if this is the first load.
+	fn queue_load(&self, load: Box<dyn FnOnce() + Send>) {
+		let mut load_queue = self.load_queue.lock();
+
+		if load_queue.is_none() {
+			let (send, recv) = channel::unbounded::<Box<dyn FnOnce() + Send>>();
+
+			for _ in 0..::num_cpus::get() {
+				let recv = recv.clone();
+
+				thread::spawn(move || {
+					while let Ok(load) = recv.recv() {
+						load();
+					}
+				});
+			}
+
+			*load_queue = Some(send);
+		}
+
+		load_queue.as_ref().unwrap().send(load).unwrap();
+	}
+
+	/// Same as `load_image()`, but the conversion and upload of the image are done on the
+	/// atlas's load threads instead of blocking the current thread.
+	pub fn load_image_async(
+		self: &Arc<Self>,
+		cache_id: SubImageCacheID,
+		image: Image,
+	) -> AtlasLoadHandle {
+		self.load_async(move |atlas| atlas.load_image(cache_id, image))
+	}
+
+	/// Same as `load_image_from_bytes()`, but decoding is done on the atlas's load threads
+	/// instead of blocking the current thread.
+	pub fn load_image_from_bytes_async(
+		self: &Arc<Self>,
+		cache_id: SubImageCacheID,
+		bytes: Vec<u8>,
+	) -> AtlasLoadHandle {
+		self.load_async(move |atlas| atlas.load_image_from_bytes(cache_id, bytes))
+	}
+
+	/// Same as `load_image_from_path()`, but reading and decoding of the file are done on the
+	/// atlas's load threads instead of blocking the current thread.
+	pub fn load_image_from_path_async<P: Into<PathBuf>>(
+		self: &Arc<Self>,
+		path: P,
+	) -> AtlasLoadHandle {
+		let path_buf = path.into();
+		self.load_async(move |atlas| atlas.load_image_from_path(path_buf))
+	}
+
+	/// Same as `load_image_from_url()`, but downloading and decoding are done on the atlas's
+	/// load threads instead of blocking the current thread.
+	pub fn load_image_from_url_async<U: AsRef<str>>(
+		self: &Arc<Self>,
+		url: U,
+	) -> AtlasLoadHandle {
+		let url = url.as_ref().to_string();
+		self.load_async(move |atlas| atlas.load_image_from_url(url))
 	}
 }
 
+#[derive(Clone)]
 struct Region {
 	x: usize,
 	y: usize,
@@ -770,23 +2232,29 @@ impl Region {
 
 struct SubImage {
 	coords: Coords,
+	region: Region,
 	img: Image,
+	/// Set when moved by a compaction. The storage image and coords it is copied from until
+	/// every storage image contains it.
+	copy_from: Option<(Arc<BstImageView>, Coords)>,
 }
 
 struct AtlasImage {
 	basalt: Arc<Basalt>,
+	format: AtlasFormat,
 	active: Option<usize>,
 	update: Option<usize>,
 	sto_imgs: Vec<Arc<BstImageView>>,
 	sub_imgs: HashMap<SubImageID, SubImage>,
 	sto_leases: Vec<Vec<Arc<AtomicBool>>>,
+	sto_clear: Vec<Vec<Coords>>,
 	con_sub_img: Vec<Vec<SubImageID>>,
 	alloc_cell_w: usize,
 	alloc: Vec<Vec<Option<SubImageID>>>,
 }
 
 impl AtlasImage {
-	fn new(basalt: Arc<Basalt>) -> Self {
+	fn new(basalt: Arc<Basalt>, format: AtlasFormat) -> Self {
 		let max_img_w = basalt.limits().max_image_dimension_2d as f32 + CELL_PAD as f32;
 		let alloc_cell_w = (max_img_w / (CELL_WIDTH + CELL_PAD) as f32).floor() as usize;
 		let mut alloc = Vec::with_capacity(alloc_cell_w);
@@ -798,12 +2266,14 @@ impl AtlasImage {
 
 		AtlasImage {
 			basalt,
+			format,
 			alloc,
 			alloc_cell_w,
 			active: None,
 			update: None,
 			sto_imgs: Vec::new(),
 			sto_leases: Vec::new(),
+			sto_clear: Vec::new(),
 			sub_imgs: HashMap::new(),
 			con_sub_img: Vec::new(),
 		}
@@ -874,7 +2344,7 @@ impl AtlasImage {
 						height: min_img_h,
 						array_layers: 1,
 					},
-					vulkano::format::Format::A8B8G8R8SrgbPack32,
+					self.format.vk_format(),
 					VkImageUsage {
 						transfer_source: true,
 						transfer_destination: true,
@@ -902,7 +2372,7 @@ impl AtlasImage {
 				let r_w = min_img_w - cur_img_w;
 				let r_h = cur_img_h;
 				let mut r_zeros = Vec::new();
-				r_zeros.resize((r_w * r_h * 4) as usize, 0);
+				r_zeros.resize((r_w * r_h * self.format.pixel_size()) as usize, 0);
 
 				let r_buf = CpuAccessibleBuffer::from_iter(
 					self.basalt.device(),
@@ -930,7 +2400,7 @@ impl AtlasImage {
 				let b_w = min_img_w;
 				let b_h = min_img_h - cur_img_h;
 				let mut b_zeros = Vec::new();
-				b_zeros.resize((b_w * b_h * 4) as usize, 0);
+				b_zeros.resize((b_w * b_h * self.format.pixel_size()) as usize, 0);
 
 				let b_buf = CpuAccessibleBuffer::from_iter(
 					self.basalt.device(),
@@ -980,6 +2450,7 @@ impl AtlasImage {
 				self.sto_imgs.push(image.clone());
 				self.con_sub_img.push(Vec::new());
 				self.sto_leases.push(Vec::new());
+				self.sto_clear.push(Vec::new());
 				found_op = Some((img_i, image));
 				self.update = Some(img_i);
 				cur_img_w = min_img_w;
@@ -988,39 +2459,127 @@ impl AtlasImage {
 		}
 
 		let (img_i, sto_img) = found_op.unwrap();
+		let clear_regions: Vec<Coords> = self.sto_clear[img_i].drain(..).collect();
 		let mut upload_data = Vec::new();
 		let mut copy_cmds = Vec::new();
+		let mut gpu_copy_cmds = Vec::new();
 
 		for (sub_img_id, sub_img) in &self.sub_imgs {
 			if !self.con_sub_img[img_i].contains(sub_img_id) {
-				if let ImageData::D8(sub_img_data) = &sub_img.img.data {
-					assert!(ImageType::SRGBA == sub_img.img.ty);
-					assert!(!sub_img_data.is_empty());
-
-					let s = upload_data.len();
-					upload_data.extend_from_slice(&sub_img_data);
-
-					copy_cmds.push((
-						s,
-						upload_data.len(),
-						sub_img.coords.x,
-						sub_img.coords.y,
-						sub_img.coords.w,
-						sub_img.coords.h,
-					));
-
-					self.con_sub_img[img_i].push(*sub_img_id);
-				} else {
-					unreachable!()
+				self.con_sub_img[img_i].push(*sub_img_id);
+
+				if let Some((src_img, src_coords)) = sub_img.copy_from.as_ref() {
+					gpu_copy_cmds.push((src_img.clone(), *src_coords, sub_img.coords));
+					continue;
 				}
+
+				let s = upload_data.len();
+
+				match (self.format, &sub_img.img.data) {
+					(AtlasFormat::SRGBA8, ImageData::D8(sub_img_data)) => {
+						assert!(ImageType::SRGBA == sub_img.img.ty);
+						assert!(!sub_img_data.is_empty());
+						upload_data.extend_from_slice(&sub_img_data);
+					},
+					(AtlasFormat::LRGBA16, ImageData::D16(sub_img_data)) => {
+						assert!(ImageType::LRGBA == sub_img.img.ty);
+						assert!(!sub_img_data.is_empty());
+
+						for v in sub_img_data {
+							upload_data.extend_from_slice(&v.to_ne_bytes());
+						}
+					},
+					_ => unreachable!(),
+				}
+
+				copy_cmds.push((
+					s,
+					upload_data.len(),
+					sub_img.coords.x,
+					sub_img.coords.y,
+					sub_img.coords.w,
+					sub_img.coords.h,
+				));
 			}
 		}
 
-		if copy_cmds.is_empty() {
+		// Once every storage image contains a moved sub image its source is no longer needed.
+		// Storage images created later are given the retained data like other sub images.
+		let con_sub_img = &self.con_sub_img;
+
+		for (sub_img_id, sub_img) in self.sub_imgs.iter_mut() {
+			if sub_img.copy_from.is_some()
+				&& con_sub_img.iter().all(|con_sub_img| con_sub_img.contains(sub_img_id))
+			{
+				sub_img.copy_from = None;
+			}
+		}
+
+		if copy_cmds.is_empty() && gpu_copy_cmds.is_empty() && clear_regions.is_empty() {
 			self.update = None;
 			return (cmd_buf, false, cur_img_w, cur_img_h);
 		}
 
+		if !clear_regions.is_empty() {
+			let mut zeros = Vec::new();
+			let mut clear_cmds = Vec::new();
+
+			for coords in clear_regions {
+				let s = zeros.len();
+				zeros.resize(
+					s + (coords.w * coords.h * self.format.pixel_size()) as usize,
+					0_u8,
+				);
+				clear_cmds.push((s, zeros.len(), coords));
+			}
+
+			let zero_buf = CpuAccessibleBuffer::from_iter(
+				self.basalt.device(),
+				VkBufferUsage {
+					transfer_source: true,
+					..VkBufferUsage::none()
+				},
+				false,
+				zeros.into_iter(),
+			)
+			.unwrap();
+
+			for (s, e, coords) in clear_cmds {
+				cmd_buf
+					.copy_buffer_to_image_dimensions(
+						zero_buf.clone().into_buffer_slice().slice(s..e).unwrap(),
+						sto_img.clone(),
+						[coords.x, coords.y, 0],
+						[coords.w, coords.h, 0],
+						0,
+						1,
+						0,
+					)
+					.unwrap();
+			}
+		}
+
+		for (src_img, src_coords, coords) in gpu_copy_cmds {
+			cmd_buf
+				.copy_image(
+					src_img,
+					[src_coords.x as i32, src_coords.y as i32, 0],
+					0,
+					0,
+					sto_img.clone(),
+					[coords.x as i32, coords.y as i32, 0],
+					0,
+					0,
+					[coords.w, coords.h, 1],
+					1,
+				)
+				.unwrap();
+		}
+
+		if copy_cmds.is_empty() {
+			return (cmd_buf, true, cur_img_w, cur_img_h);
+		}
+
 		let upload_buf = CpuAccessibleBuffer::from_iter(
 			self.basalt.device(),
 			VkBufferUsage {
@@ -1121,7 +2680,14 @@ impl AtlasImage {
 		})
 	}
 
-	fn insert(&mut self, region: &Region, sub_img_id: SubImageID, coords: Coords, img: Image) {
+	fn insert(
+		&mut self,
+		region: &Region,
+		sub_img_id: SubImageID,
+		coords: Coords,
+		img: Image,
+		copy_from: Option<(Arc<BstImageView>, Coords)>,
+	) {
 		for x in region.x..(region.x + region.w) {
 			for y in region.y..(region.y + region.h) {
 				self.alloc[x][y] = Some(sub_img_id);
@@ -1130,7 +2696,202 @@ impl AtlasImage {
 
 		self.sub_imgs.insert(sub_img_id, SubImage {
 			coords,
+			region: region.clone(),
 			img,
+			copy_from,
+		});
+	}
+
+	fn stats(&self, img_id: AtlasImageID) -> AtlasImageStats {
+		let (cells_used, cells_spanned) = self.cell_usage();
+		let mut width = 0;
+		let mut height = 0;
+		let mut gpu_bytes = 0;
+
+		for sto_img in &self.sto_imgs {
+			let dims = sto_img.dimensions();
+			gpu_bytes += dims.width() as usize
+				* dims.height() as usize
+				* self.format.pixel_size() as usize;
+
+			if dims.width() > width {
+				width = dims.width();
+			}
+
+			if dims.height() > height {
+				height = dims.height();
+			}
+		}
+
+		AtlasImageStats {
+			img_id,
+			sub_images: self.sub_imgs.len(),
+			cells_used,
+			cells_spanned,
+			cells_total: self.alloc_cell_w * self.alloc_cell_w,
+			width,
+			height,
+			gpu_images: self.sto_imgs.len(),
+			gpu_bytes,
+			data_bytes: self.sub_imgs.values().map(|sub_img| sub_img.img.data_size()).sum(),
+		}
+	}
+
+	/// Ratio of unused cells within the area spanned by sub images across all atlas images.
+	fn fragmentation(atlas_images: &[AtlasImage]) -> f32 {
+		let mut used = 0;
+		let mut extent = 0;
+
+		for atlas_image in atlas_images {
+			let (img_used, img_extent) = atlas_image.cell_usage();
+			used += img_used;
+			extent += img_extent;
+		}
+
+		if extent == 0 {
+			0.0
+		} else {
+			1.0 - (used as f32 / extent as f32)
+		}
+	}
+
+	/// Returns the amount of cells used by sub images along with the amount of cells within
+	/// the area spanned by them.
+	fn cell_usage(&self) -> (usize, usize) {
+		let mut used = 0;
+		let mut max_x = 0;
+		let mut max_y = 0;
+
+		for sub_img in self.sub_imgs.values() {
+			used += sub_img.region.w * sub_img.region.h;
+
+			if sub_img.region.x + sub_img.region.w > max_x {
+				max_x = sub_img.region.x + sub_img.region.w;
+			}
+
+			if sub_img.region.y + sub_img.region.h > max_y {
+				max_y = sub_img.region.y + sub_img.region.h;
+			}
+		}
+
+		(used, max_x * max_y)
+	}
+
+	/// Repack the sub images of the atlas images whose fragmentation is above the threshold.
+	/// These atlas images are replaced with empty ones at the same index and their sub images
+	/// are placed again, larger ones first, in the first atlas image they fit in. Returns the
+	/// coords before and after of the sub images whose coords changed keyed by the image and
+	/// sub image ids of the coords before.
+	fn compact(
+		basalt: Arc<Basalt>,
+		atlas_images: &mut Vec<AtlasImage>,
+		threshold: f32,
+	) -> HashMap<(AtlasImageID, SubImageID), (Coords, Coords)> {
+		let mut sub_imgs: Vec<(SubImageID, SubImage)> = Vec::new();
+
+		for atlas_image in atlas_images.iter_mut() {
+			let (used, spanned) = atlas_image.cell_usage();
+
+			if spanned == 0 || 1.0 - (used as f32 / spanned as f32) <= threshold {
+				continue;
+			}
+
+			let format = atlas_image.format;
+			let AtlasImage {
+				sto_imgs,
+				sub_imgs: fragmented,
+				con_sub_img,
+				active,
+				..
+			} = ::std::mem::replace(atlas_image, AtlasImage::new(basalt.clone(), format));
+
+			for (sub_img_id, mut sub_img) in fragmented {
+				// Sub images not yet in the active storage image keep their previous source,
+				// or if they don't have one are uploaded from the retained data.
+				if let Some(active) = active {
+					if con_sub_img[active].contains(&sub_img_id) {
+						sub_img.copy_from = Some((sto_imgs[active].clone(), sub_img.coords));
+					}
+				}
+
+				sub_imgs.push((sub_img_id, sub_img));
+			}
+		}
+
+		sub_imgs.sort_by_key(|(sub_img_id, sub_img)| {
+			(
+				::std::cmp::Reverse(sub_img.region.h),
+				::std::cmp::Reverse(sub_img.region.w),
+				*sub_img_id,
+			)
 		});
+
+		let mut moved = HashMap::new();
+
+		for (sub_img_id, sub_img) in sub_imgs {
+			let format = sub_img.img.atlas_format();
+			let mut space_op = None;
+
+			for (i, atlas_image) in atlas_images.iter().enumerate() {
+				if atlas_image.format != format {
+					continue;
+				}
+
+				if let Some(region) = atlas_image.find_space_for(&sub_img.img.dims) {
+					space_op = Some((i + 1, region));
+					break;
+				}
+			}
+
+			if space_op.is_none() {
+				let atlas_image = AtlasImage::new(basalt.clone(), format);
+
+				// Previously fit within an atlas image, so it will fit in an empty one.
+				let region = atlas_image.find_space_for(&sub_img.img.dims).unwrap();
+				space_op = Some((atlas_images.len() + 1, region));
+				atlas_images.push(atlas_image);
+			}
+
+			let (atlas_image_i, region) = space_op.unwrap();
+			let coords = region.coords(atlas_image_i as u64, sub_img_id, &sub_img.img.dims);
+
+			if coords != sub_img.coords {
+				moved.insert((sub_img.coords.img_id, sub_img_id), (sub_img.coords, coords));
+			}
+
+			atlas_images[atlas_image_i - 1].insert(
+				&region,
+				sub_img_id,
+				coords,
+				sub_img.img,
+				sub_img.copy_from,
+			);
+		}
+
+		moved
+	}
+
+	/// Remove a sub image, freeing its cells and queueing its area to be cleared on every
+	/// storage image. Returns false if the sub image isn't in this atlas image.
+	fn remove(&mut self, sub_img_id: SubImageID) -> bool {
+		let sub_img = match self.sub_imgs.remove(&sub_img_id) {
+			Some(some) => some,
+			None => return false,
+		};
+
+		for x in sub_img.region.x..(sub_img.region.x + sub_img.region.w) {
+			for y in sub_img.region.y..(sub_img.region.y + sub_img.region.h) {
+				self.alloc[x][y] = None;
+			}
+		}
+
+		for (i, con_sub_img) in self.con_sub_img.iter_mut().enumerate() {
+			if let Some(j) = con_sub_img.iter().position(|id| *id == sub_img_id) {
+				con_sub_img.swap_remove(j);
+				self.sto_clear[i].push(sub_img.coords);
+			}
+		}
+
+		true
 	}
 }
@@ -0,0 +1,951 @@
+use super::style::{AlignItems, BinStyle, FlexDirection, FlexWrap, GridTrack, JustifyContent};
+
+/// The properties of a flex parent needed to lay out its children. Sizes are of the area
+/// within the parent's padding.
+pub(crate) struct FlexContainer {
+	pub width: f32,
+	pub height: f32,
+	pub direction: FlexDirection,
+	pub wrap: FlexWrap,
+	pub justify: JustifyContent,
+	pub align: AlignItems,
+	pub row_gap: f32,
+	pub column_gap: f32,
+}
+
+impl FlexContainer {
+	pub fn from_style(style: &BinStyle, width: f32, height: f32) -> Self {
+		FlexContainer {
+			width,
+			height,
+			direction: style.flex_direction.clone().unwrap_or(FlexDirection::Row),
+			wrap: style.flex_wrap.clone().unwrap_or(FlexWrap::NoWrap),
+			justify: style.justify_content.clone().unwrap_or(JustifyContent::Start),
+			align: style.align_items.clone().unwrap_or(AlignItems::Stretch),
+			row_gap: style.row_gap.unwrap_or(0.0),
+			column_gap: style.column_gap.unwrap_or(0.0),
+		}
+	}
+
+	fn is_row(&self) -> bool {
+		match self.direction {
+			FlexDirection::Row | FlexDirection::RowReverse => true,
+			FlexDirection::Column | FlexDirection::ColumnReverse => false,
+		}
+	}
+
+	fn is_reverse(&self) -> bool {
+		match self.direction {
+			FlexDirection::RowReverse | FlexDirection::ColumnReverse => true,
+			FlexDirection::Row | FlexDirection::Column => false,
+		}
+	}
+}
+
+/// A child of a flex parent. `width` and `height` are `None` when the style doesn't specify
+/// them.
+pub(crate) struct FlexItem {
+	pub width: Option<f32>,
+	pub height: Option<f32>,
+	pub basis: Option<f32>,
+	pub grow: f32,
+	pub shrink: f32,
+	pub align: Option<AlignItems>,
+	/// Minimum width and height
+	pub min: [f32; 2],
+	/// Maximum width and height
+	pub max: [f32; 2],
+	pub margin_t: f32,
+	pub margin_b: f32,
+	pub margin_l: f32,
+	pub margin_r: f32,
+}
+
+impl FlexItem {
+	pub fn from_style(style: &BinStyle, parent_w: f32, parent_h: f32) -> Self {
+		let (width, height) = style_size(style, parent_w, parent_h);
+		let (min, max) = style.size_limits(parent_w, parent_h);
+
+		FlexItem {
+			width,
+			height,
+			basis: style.flex_basis,
+			grow: style.flex_grow.unwrap_or(0.0),
+			shrink: style.flex_shrink.unwrap_or(1.0),
+			align: style.align_self.clone(),
+			min,
+			max,
+			margin_t: style.margin_t.unwrap_or(0.0),
+			margin_b: style.margin_b.unwrap_or(0.0),
+			margin_l: style.margin_l.unwrap_or(0.0),
+			margin_r: style.margin_r.unwrap_or(0.0),
+		}
+	}
+}
+
+/// Width and height of a child from its style, `None` where the style doesn't specify them.
+fn style_size(style: &BinStyle, parent_w: f32, parent_h: f32) -> (Option<f32>, Option<f32>) {
+	let width = match style.width {
+		Some(some) => Some(some),
+		None => style.width_pct.map(|pct| (pct / 100.0) * parent_w),
+	}
+	.map(|width| width + style.width_offset.unwrap_or(0.0));
+
+	let height = match style.height {
+		Some(some) => Some(some),
+		None => style.height_pct.map(|pct| (pct / 100.0) * parent_h),
+	}
+	.map(|height| height + style.height_offset.unwrap_or(0.0));
+
+	(width, height)
+}
+
+/// Item sizes and margins along the main and cross axis of a flex parent.
+struct Axis {
+	/// Main size before limits are applied.
+	basis: f32,
+	/// Main size with limits applied.
+	main: f32,
+	cross: Option<f32>,
+	main_margin: (f32, f32),
+	cross_margin: (f32, f32),
+	main_limits: (f32, f32),
+	cross_limits: (f32, f32),
+}
+
+/// Lay out the children of a flex parent. Returns the top, left, width and height of each
+/// item relative to the parent's padding.
+pub(crate) fn flex(container: &FlexContainer, items: &[FlexItem]) -> Vec<[f32; 4]> {
+	let is_row = container.is_row();
+
+	let (main_size, cross_size, main_gap, cross_gap) = if is_row {
+		(container.width, container.height, container.column_gap, container.row_gap)
+	} else {
+		(container.height, container.width, container.row_gap, container.column_gap)
+	};
+
+	let axes: Vec<Axis> = items
+		.iter()
+		.map(|item| {
+			if is_row {
+				let basis = item.basis.or(item.width).unwrap_or(0.0);
+
+				Axis {
+					basis,
+					main: basis.min(item.max[0]).max(item.min[0]),
+					cross: item.height,
+					main_margin: (item.margin_l, item.margin_r),
+					cross_margin: (item.margin_t, item.margin_b),
+					main_limits: (item.min[0], item.max[0]),
+					cross_limits: (item.min[1], item.max[1]),
+				}
+			} else {
+				let basis = item.basis.or(item.height).unwrap_or(0.0);
+
+				Axis {
+					basis,
+					main: basis.min(item.max[1]).max(item.min[1]),
+					cross: item.width,
+					main_margin: (item.margin_t, item.margin_b),
+					cross_margin: (item.margin_l, item.margin_r),
+					main_limits: (item.min[1], item.max[1]),
+					cross_limits: (item.min[0], item.max[0]),
+				}
+			}
+		})
+		.collect();
+
+	// Break the items into lines.
+	let mut lines: Vec<Vec<usize>> = Vec::new();
+	let mut line: Vec<usize> = Vec::new();
+	let mut line_main = 0.0;
+
+	for (i, axis) in axes.iter().enumerate() {
+		let outer = axis.main_margin.0 + axis.main + axis.main_margin.1;
+
+		if container.wrap != FlexWrap::NoWrap
+			&& !line.is_empty()
+			&& line_main + main_gap + outer > main_size
+		{
+			lines.push(line.split_off(0));
+			line_main = 0.0;
+		}
+
+		if !line.is_empty() {
+			line_main += main_gap;
+		}
+
+		line_main += outer;
+		line.push(i);
+	}
+
+	if !line.is_empty() {
+		lines.push(line);
+	}
+
+	// (main_pos, main_size, cross_pos, cross_size) of each item
+	let mut placed = vec![(0.0, 0.0, 0.0, 0.0); items.len()];
+	let mut line_cross_pos = 0.0;
+	let single_line = lines.len() == 1 && container.wrap == FlexWrap::NoWrap;
+
+	for line in &lines {
+		let margins: f32 =
+			line.iter().map(|i| axes[*i].main_margin.0 + axes[*i].main_margin.1).sum();
+		let gaps = main_gap * (line.len() - 1) as f32;
+		let sizes = flex_line_sizes(line, items, &axes, main_size - margins - gaps);
+
+		// Space the items don't fill is distributed by `justify_content`.
+		let free = (main_size - sizes.iter().sum::<f32>() - margins - gaps).max(0.0);
+		let count = line.len() as f32;
+
+		let (mut main_pos, spacing) = match container.justify {
+			JustifyContent::Start => (0.0, 0.0),
+			JustifyContent::End => (free, 0.0),
+			JustifyContent::Center => (free / 2.0, 0.0),
+			JustifyContent::SpaceBetween =>
+				if line.len() > 1 {
+					(0.0, free / (count - 1.0))
+				} else {
+					(0.0, 0.0)
+				},
+			JustifyContent::SpaceAround => (free / count / 2.0, free / count),
+			JustifyContent::SpaceEvenly => (free / (count + 1.0), free / (count + 1.0)),
+		};
+
+		let line_cross = if single_line {
+			cross_size
+		} else {
+			line.iter()
+				.map(|i| {
+					axes[*i].cross.unwrap_or(0.0)
+						+ axes[*i].cross_margin.0
+						+ axes[*i].cross_margin.1
+				})
+				.fold(0.0, f32::max)
+		};
+
+		for (j, i) in line.iter().enumerate() {
+			let axis = &axes[*i];
+			main_pos += axis.main_margin.0;
+			let margin_cross = axis.cross_margin.0 + axis.cross_margin.1;
+			let align = items[*i].align.clone().unwrap_or_else(|| container.align.clone());
+
+			let item_cross = match axis.cross {
+				Some(cross) => cross,
+				None =>
+					match align {
+						AlignItems::Stretch => (line_cross - margin_cross).max(0.0),
+						_ => 0.0,
+					},
+			}
+			.min(axis.cross_limits.1)
+			.max(axis.cross_limits.0);
+
+			let cross_offset = match align {
+				AlignItems::Start | AlignItems::Stretch => 0.0,
+				AlignItems::End => line_cross - margin_cross - item_cross,
+				AlignItems::Center => (line_cross - margin_cross - item_cross) / 2.0,
+			};
+
+			placed[*i] = (
+				main_pos,
+				sizes[j],
+				line_cross_pos + axis.cross_margin.0 + cross_offset,
+				item_cross,
+			);
+
+			main_pos += sizes[j] + axis.main_margin.1 + main_gap + spacing;
+		}
+
+		line_cross_pos += line_cross + cross_gap;
+	}
+
+	placed
+		.into_iter()
+		.map(|(mut main_pos, main, mut cross_pos, cross)| {
+			if container.is_reverse() {
+				main_pos = main_size - main_pos - main;
+			}
+
+			if container.wrap == FlexWrap::WrapReverse {
+				cross_pos = cross_size - cross_pos - cross;
+			}
+
+			if is_row {
+				[cross_pos, main_pos, main, cross]
+			} else {
+				[main_pos, cross_pos, cross, main]
+			}
+		})
+		.collect()
+}
+
+/// Main sizes of the items of a line that fill `available`. Each item that can grow or shrink
+/// takes its share of the free space. Items that pass their limits are clamped and frozen, and
+/// the free space is shared again among the rest until every item is frozen. This way space a
+/// limited item can't take, or takes beyond its share, is given to or taken from the others.
+fn flex_line_sizes(
+	line: &[usize],
+	items: &[FlexItem],
+	axes: &[Axis],
+	available: f32,
+) -> Vec<f32> {
+	let growing = line.iter().map(|i| axes[*i].main).sum::<f32>() < available;
+	let mut sizes: Vec<f32> = line.iter().map(|i| axes[*i].main).collect();
+
+	// Items that can't flex in this direction, or whose limits already move them against it,
+	// keep their limited size.
+	let mut frozen: Vec<bool> = line
+		.iter()
+		.map(|i| {
+			let (item, axis) = (&items[*i], &axes[*i]);
+
+			match growing {
+				true => item.grow <= 0.0 || axis.basis > axis.main,
+				false => item.shrink <= 0.0 || axis.basis < axis.main,
+			}
+		})
+		.collect();
+
+	while frozen.iter().any(|frozen| !frozen) {
+		let used: f32 = line
+			.iter()
+			.enumerate()
+			.map(|(j, i)| {
+				match frozen[j] {
+					true => sizes[j],
+					false => axes[*i].basis,
+				}
+			})
+			.sum();
+
+		let free = available - used;
+
+		// Shrinking is weighted by the basis so that small items don't disappear first.
+		let weights: Vec<f32> = line
+			.iter()
+			.enumerate()
+			.map(|(j, i)| {
+				match (frozen[j], growing) {
+					(true, _) => 0.0,
+					(false, true) => items[*i].grow,
+					(false, false) => items[*i].shrink * axes[*i].basis,
+				}
+			})
+			.collect();
+
+		let weight: f32 = weights.iter().sum();
+		let mut violations = vec![0.0; line.len()];
+		let mut violation = 0.0;
+
+		for (j, i) in line.iter().enumerate() {
+			if frozen[j] {
+				continue;
+			}
+
+			let axis = &axes[*i];
+
+			let target = match weight > 0.0 {
+				true => axis.basis + (free * (weights[j] / weight)),
+				false => axis.basis,
+			};
+
+			sizes[j] = target.min(axis.main_limits.1).max(axis.main_limits.0).max(0.0);
+			violations[j] = sizes[j] - target;
+			violation += violations[j];
+		}
+
+		// With no overall violation every item is done. Otherwise only the items violating in
+		// the same direction as the total are frozen, and the others are flexed again.
+		for j in 0..line.len() {
+			if !frozen[j] {
+				frozen[j] = violation == 0.0
+					|| (violation > 0.0 && violations[j] > 0.0)
+					|| (violation < 0.0 && violations[j] < 0.0);
+			}
+		}
+	}
+
+	sizes
+}
+
+/// The properties of a grid parent needed to lay out its children. Sizes are of the area
+/// within the parent's padding.
+pub(crate) struct GridContainer {
+	pub width: f32,
+	pub height: f32,
+	pub columns: Vec<GridTrack>,
+	pub rows: Vec<GridTrack>,
+	pub row_gap: f32,
+	pub column_gap: f32,
+}
+
+impl GridContainer {
+	pub fn from_style(style: &BinStyle, width: f32, height: f32) -> Self {
+		GridContainer {
+			width,
+			height,
+			columns: style.grid_columns.clone().unwrap_or_default(),
+			rows: style.grid_rows.clone().unwrap_or_default(),
+			row_gap: style.row_gap.unwrap_or(0.0),
+			column_gap: style.column_gap.unwrap_or(0.0),
+		}
+	}
+}
+
+/// A child of a grid parent. Children without a `column` or `row` are placed in the next
+/// free cell.
+pub(crate) struct GridItem {
+	pub column: Option<usize>,
+	pub row: Option<usize>,
+	pub column_span: usize,
+	pub row_span: usize,
+	pub width: Option<f32>,
+	pub height: Option<f32>,
+	/// Minimum width and height
+	pub min: [f32; 2],
+	/// Maximum width and height
+	pub max: [f32; 2],
+	pub margin_t: f32,
+	pub margin_b: f32,
+	pub margin_l: f32,
+	pub margin_r: f32,
+}
+
+impl GridItem {
+	pub fn from_style(style: &BinStyle, parent_w: f32, parent_h: f32) -> Self {
+		let (width, height) = style_size(style, parent_w, parent_h);
+		let (min, max) = style.size_limits(parent_w, parent_h);
+
+		GridItem {
+			column: style.grid_column,
+			row: style.grid_row,
+			column_span: style.grid_column_span.unwrap_or(1).max(1),
+			row_span: style.grid_row_span.unwrap_or(1).max(1),
+			width,
+			height,
+			min,
+			max,
+			margin_t: style.margin_t.unwrap_or(0.0),
+			margin_b: style.margin_b.unwrap_or(0.0),
+			margin_l: style.margin_l.unwrap_or(0.0),
+			margin_r: style.margin_r.unwrap_or(0.0),
+		}
+	}
+}
+
+/// Lay out the children of a grid parent. Returns the top, left, width and height of each
+/// item relative to the parent's padding.
+pub(crate) fn grid(container: &GridContainer, items: &[GridItem]) -> Vec<[f32; 4]> {
+	// Columns placed outside of the defined ones are added as auto sized columns.
+	let mut column_count = items
+		.iter()
+		.filter_map(|item| item.column.map(|column| column + item.column_span))
+		.fold(container.columns.len().max(1), usize::max);
+
+	// Cells outside of those occupied so far are free.
+	let mut occupied: Vec<Vec<bool>> = Vec::new();
+
+	let is_free =
+		|occupied: &Vec<Vec<bool>>, row: usize, column: usize, rs: usize, cs: usize| {
+			(row..(row + rs)).all(|r| {
+				(column..(column + cs)).all(|c| {
+					!occupied.get(r).and_then(|cells| cells.get(c)).cloned().unwrap_or(false)
+				})
+			})
+		};
+
+	let occupy =
+		|occupied: &mut Vec<Vec<bool>>, row: usize, column: usize, rs: usize, cs: usize| {
+			while occupied.len() < row + rs {
+				occupied.push(Vec::new());
+			}
+
+			for cells in &mut occupied[row..(row + rs)] {
+				if cells.len() < column + cs {
+					cells.resize(column + cs, false);
+				}
+
+				for cell in &mut cells[column..(column + cs)] {
+					*cell = true;
+				}
+			}
+		};
+
+	// (row, column, row_span, column_span) of each item
+	let mut cells = vec![(0, 0, 1, 1); items.len()];
+
+	// Items with both a row and column take their cell first.
+	for (i, item) in items.iter().enumerate() {
+		if let (Some(row), Some(column)) = (item.row, item.column) {
+			let cs = item.column_span.min(column_count - column);
+			occupy(&mut occupied, row, column, item.row_span, cs);
+			cells[i] = (row, column, item.row_span, cs);
+		}
+	}
+
+	let mut cursor = (0, 0);
+
+	for (i, item) in items.iter().enumerate() {
+		if item.row.is_some() && item.column.is_some() {
+			continue;
+		}
+
+		let cs = item.column_span.min(column_count);
+		let rs = item.row_span;
+
+		let (row, column) = match (item.row, item.column) {
+			(Some(row), None) => {
+				// If the row is full, columns are added for the item.
+				let column =
+					(0..).find(|column| is_free(&occupied, row, *column, rs, cs)).unwrap();
+				column_count = column_count.max(column + cs);
+				(row, column)
+			},
+			(None, Some(column)) => {
+				let cs = item.column_span.min(column_count - column);
+				let row = (0..).find(|row| is_free(&occupied, *row, column, rs, cs)).unwrap();
+				(row, column)
+			},
+			_ => {
+				let (mut row, mut column) = cursor;
+
+				while column + cs > column_count || !is_free(&occupied, row, column, rs, cs) {
+					column += 1;
+
+					if column >= column_count {
+						column = 0;
+						row += 1;
+					}
+				}
+
+				cursor = (row, column);
+				(row, column)
+			},
+		};
+
+		let cs = cs.min(column_count - column);
+		occupy(&mut occupied, row, column, rs, cs);
+		cells[i] = (row, column, rs, cs);
+	}
+
+	let row_count = container.rows.len().max(occupied.len());
+
+	let column_sizes = track_sizes(
+		&container.columns,
+		column_count,
+		container.width,
+		container.column_gap,
+		items.iter().zip(cells.iter()).map(|(item, cell)| {
+			(cell.1, cell.3, item.width.map(|w| w + item.margin_l + item.margin_r))
+		}),
+	);
+
+	let row_sizes = track_sizes(
+		&container.rows,
+		row_count,
+		container.height,
+		container.row_gap,
+		items.iter().zip(cells.iter()).map(|(item, cell)| {
+			(cell.0, cell.2, item.height.map(|h| h + item.margin_t + item.margin_b))
+		}),
+	);
+
+	let offset = |sizes: &[f32], gap: f32, start: usize, span: usize| -> (f32, f32) {
+		let pos = sizes[..start].iter().map(|size| size + gap).sum();
+		let size = sizes[start..(start + span)].iter().sum::<f32>() + (gap * (span - 1) as f32);
+		(pos, size)
+	};
+
+	items
+		.iter()
+		.zip(cells)
+		.map(|(item, (row, column, rs, cs))| {
+			let (left, cell_w) = offset(&column_sizes, container.column_gap, column, cs);
+			let (top, cell_h) = offset(&row_sizes, container.row_gap, row, rs);
+			let width = item
+				.width
+				.unwrap_or(cell_w - item.margin_l - item.margin_r)
+				.min(item.max[0])
+				.max(item.min[0]);
+			let height = item
+				.height
+				.unwrap_or(cell_h - item.margin_t - item.margin_b)
+				.min(item.max[1])
+				.max(item.min[1]);
+			[top + item.margin_t, left + item.margin_l, width, height]
+		})
+		.collect()
+}
+
+/// Size the tracks along one axis. `items` provides the start track, span and outer size of
+/// each item. Tracks beyond those defined are sized as `GridTrack::Auto`.
+fn track_sizes<I: Iterator<Item = (usize, usize, Option<f32>)>>(
+	defined: &[GridTrack],
+	count: usize,
+	space: f32,
+	gap: f32,
+	items: I,
+) -> Vec<f32> {
+	let mut auto = vec![0.0_f32; count];
+
+	// Only items spanning a single track contribute to the size of auto tracks.
+	for (start, span, size) in items {
+		if let (1, Some(size)) = (span, size) {
+			auto[start] = auto[start].max(size);
+		}
+	}
+
+	let mut sizes = vec![0.0; count];
+	let mut fractions = 0.0;
+
+	for (i, size) in sizes.iter_mut().enumerate() {
+		match defined.get(i).cloned().unwrap_or(GridTrack::Auto) {
+			GridTrack::Fixed(fixed) => *size = fixed,
+			GridTrack::Percent(pct) => *size = (pct / 100.0) * space,
+			GridTrack::Fraction(fr) => fractions += fr,
+			GridTrack::Auto => *size = auto[i],
+		}
+	}
+
+	if fractions > 0.0 {
+		let used: f32 = sizes.iter().sum::<f32>() + (gap * count.saturating_sub(1) as f32);
+		let free = (space - used).max(0.0);
+
+		for (i, size) in sizes.iter_mut().enumerate() {
+			if let Some(GridTrack::Fraction(fr)) = defined.get(i) {
+				*size = free * (fr / fractions);
+			}
+		}
+	}
+
+	sizes
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn flex_item(width: f32, height: f32) -> FlexItem {
+		FlexItem {
+			width: Some(width),
+			height: Some(height),
+			basis: None,
+			grow: 0.0,
+			shrink: 1.0,
+			align: None,
+			min: [0.0, 0.0],
+			max: [f32::INFINITY, f32::INFINITY],
+			margin_t: 0.0,
+			margin_b: 0.0,
+			margin_l: 0.0,
+			margin_r: 0.0,
+		}
+	}
+
+	fn flex_container(width: f32, height: f32) -> FlexContainer {
+		FlexContainer {
+			width,
+			height,
+			direction: FlexDirection::Row,
+			wrap: FlexWrap::NoWrap,
+			justify: JustifyContent::Start,
+			align: AlignItems::Stretch,
+			row_gap: 0.0,
+			column_gap: 0.0,
+		}
+	}
+
+	fn grid_item(column: Option<usize>, row: Option<usize>) -> GridItem {
+		GridItem {
+			column,
+			row,
+			column_span: 1,
+			row_span: 1,
+			width: None,
+			height: None,
+			min: [0.0, 0.0],
+			max: [f32::INFINITY, f32::INFINITY],
+			margin_t: 0.0,
+			margin_b: 0.0,
+			margin_l: 0.0,
+			margin_r: 0.0,
+		}
+	}
+
+	fn grid_container(columns: Vec<GridTrack>, rows: Vec<GridTrack>) -> GridContainer {
+		GridContainer {
+			width: 300.0,
+			height: 300.0,
+			columns,
+			rows,
+			row_gap: 0.0,
+			column_gap: 0.0,
+		}
+	}
+
+	fn assert_near(a: f32, b: f32) {
+		assert!((a - b).abs() < 0.001, "{} != {}", a, b);
+	}
+
+	#[test]
+	fn flex_grow() {
+		let items = vec![
+			FlexItem {
+				grow: 1.0,
+				..flex_item(50.0, 10.0)
+			},
+			FlexItem {
+				grow: 2.0,
+				..flex_item(50.0, 10.0)
+			},
+			flex_item(50.0, 10.0),
+		];
+
+		let rects = flex(&flex_container(300.0, 100.0), &items);
+		assert_near(rects[0][2], 100.0);
+		assert_near(rects[1][1], 100.0);
+		assert_near(rects[1][2], 150.0);
+		assert_near(rects[2][1], 250.0);
+		assert_near(rects[2][2], 50.0);
+	}
+
+	#[test]
+	fn flex_grow_max() {
+		let items = vec![
+			FlexItem {
+				basis: Some(0.0),
+				grow: 1.0,
+				max: [50.0, f32::INFINITY],
+				..flex_item(0.0, 10.0)
+			},
+			FlexItem {
+				basis: Some(0.0),
+				grow: 1.0,
+				..flex_item(0.0, 10.0)
+			},
+			FlexItem {
+				basis: Some(0.0),
+				grow: 1.0,
+				..flex_item(0.0, 10.0)
+			},
+		];
+
+		let sizes: Vec<f32> =
+			flex(&flex_container(300.0, 100.0), &items).iter().map(|rect| rect[2]).collect();
+		assert_near(sizes[0], 50.0);
+		assert_near(sizes[1], 125.0);
+		assert_near(sizes[2], 125.0);
+	}
+
+	#[test]
+	fn flex_shrink_min() {
+		let items = vec![
+			FlexItem {
+				min: [60.0, 0.0],
+				..flex_item(100.0, 10.0)
+			},
+			flex_item(100.0, 10.0),
+			flex_item(100.0, 10.0),
+		];
+
+		let sizes: Vec<f32> =
+			flex(&flex_container(100.0, 100.0), &items).iter().map(|rect| rect[2]).collect();
+		assert_near(sizes[0], 60.0);
+		assert_near(sizes[1], 20.0);
+		assert_near(sizes[2], 20.0);
+	}
+
+	#[test]
+	fn flex_shrink_weighted() {
+		let items = vec![flex_item(200.0, 10.0), flex_item(100.0, 10.0), FlexItem {
+			shrink: 0.0,
+			..flex_item(50.0, 10.0)
+		}];
+
+		let sizes: Vec<f32> =
+			flex(&flex_container(200.0, 100.0), &items).iter().map(|rect| rect[2]).collect();
+		assert_near(sizes[0], 100.0);
+		assert_near(sizes[1], 50.0);
+		assert_near(sizes[2], 50.0);
+	}
+
+	#[test]
+	fn flex_wrap() {
+		let container = FlexContainer {
+			wrap: FlexWrap::Wrap,
+			row_gap: 5.0,
+			..flex_container(100.0, 100.0)
+		};
+
+		let items = vec![flex_item(40.0, 10.0), flex_item(40.0, 20.0), flex_item(40.0, 10.0)];
+		let rects = flex(&container, &items);
+		assert_eq!(rects[0], [0.0, 0.0, 40.0, 10.0]);
+		assert_eq!(rects[1], [0.0, 40.0, 40.0, 20.0]);
+		assert_eq!(rects[2], [25.0, 0.0, 40.0, 10.0]);
+
+		let rects = flex(
+			&FlexContainer {
+				wrap: FlexWrap::NoWrap,
+				..container
+			},
+			&items,
+		);
+
+		assert_near(rects[2][0], 0.0);
+		assert_near(rects[2][1] + rects[2][2], 100.0);
+	}
+
+	#[test]
+	fn flex_gap() {
+		let container = FlexContainer {
+			column_gap: 10.0,
+			..flex_container(100.0, 100.0)
+		};
+
+		let items = vec![flex_item(20.0, 10.0), flex_item(20.0, 10.0), flex_item(20.0, 10.0)];
+		let lefts: Vec<f32> = flex(&container, &items).iter().map(|rect| rect[1]).collect();
+		assert_eq!(lefts, vec![0.0, 30.0, 60.0]);
+
+		let items = vec![
+			FlexItem {
+				grow: 1.0,
+				..flex_item(0.0, 10.0)
+			},
+			FlexItem {
+				grow: 1.0,
+				..flex_item(0.0, 10.0)
+			},
+		];
+
+		let rects = flex(&container, &items);
+		assert_near(rects[0][2], 45.0);
+		assert_near(rects[1][1], 55.0);
+	}
+
+	#[test]
+	fn flex_line_sizes_frozen() {
+		let items = vec![
+			FlexItem {
+				grow: 1.0,
+				max: [10.0, f32::INFINITY],
+				..flex_item(0.0, 0.0)
+			},
+			FlexItem {
+				grow: 1.0,
+				..flex_item(0.0, 0.0)
+			},
+		];
+
+		let axes: Vec<Axis> = items
+			.iter()
+			.map(|item| {
+				Axis {
+					basis: 0.0,
+					main: 0.0,
+					cross: None,
+					main_margin: (0.0, 0.0),
+					cross_margin: (0.0, 0.0),
+					main_limits: (item.min[0], item.max[0]),
+					cross_limits: (0.0, f32::INFINITY),
+				}
+			})
+			.collect();
+
+		assert_eq!(flex_line_sizes(&[0, 1], &items, &axes, 100.0), vec![10.0, 90.0]);
+		assert_eq!(flex_line_sizes(&[1], &items, &axes, 100.0), vec![100.0]);
+	}
+
+	#[test]
+	fn grid_auto_placement() {
+		let container =
+			grid_container(vec![GridTrack::Fixed(50.0), GridTrack::Fixed(50.0)], Vec::new());
+
+		let items = vec![
+			grid_item(None, None),
+			grid_item(Some(0), Some(0)),
+			grid_item(None, None),
+			grid_item(Some(1), None),
+		];
+
+		let tl: Vec<[f32; 2]> =
+			grid(&container, &items).iter().map(|rect| [rect[0], rect[1]]).collect();
+
+		// Rows are auto sized and these items have no height, so every row has no height.
+		assert_eq!(tl, vec![[0.0, 50.0], [0.0, 0.0], [0.0, 0.0], [0.0, 50.0]]);
+
+		let items: Vec<GridItem> = (0..3)
+			.map(|_| {
+				GridItem {
+					height: Some(10.0),
+					..grid_item(None, None)
+				}
+			})
+			.collect();
+
+		let rects = grid(&container, &items);
+		assert_eq!(rects[0], [0.0, 0.0, 50.0, 10.0]);
+		assert_eq!(rects[1], [0.0, 50.0, 50.0, 10.0]);
+		assert_eq!(rects[2], [10.0, 0.0, 50.0, 10.0]);
+	}
+
+	#[test]
+	fn grid_full_row() {
+		let container =
+			grid_container(vec![GridTrack::Fixed(50.0), GridTrack::Fixed(50.0)], Vec::new());
+
+		let items = vec![grid_item(Some(0), Some(0)), grid_item(Some(1), Some(0)), GridItem {
+			width: Some(30.0),
+			..grid_item(None, Some(0))
+		}];
+
+		let rects = grid(&container, &items);
+		assert_eq!(rects[0][1], 0.0);
+		assert_eq!(rects[1][1], 50.0);
+		assert_eq!(rects[2], [0.0, 100.0, 30.0, 0.0]);
+	}
+
+	#[test]
+	fn grid_spans() {
+		let container = GridContainer {
+			column_gap: 10.0,
+			..grid_container(vec![GridTrack::Fixed(50.0); 3], vec![GridTrack::Fixed(20.0); 2])
+		};
+
+		let items = vec![
+			GridItem {
+				column_span: 2,
+				row_span: 2,
+				..grid_item(Some(1), Some(0))
+			},
+			grid_item(None, None),
+			grid_item(None, None),
+		];
+
+		let rects = grid(&container, &items);
+		assert_eq!(rects[0], [0.0, 60.0, 110.0, 40.0]);
+		assert_eq!(rects[1], [0.0, 0.0, 50.0, 20.0]);
+		assert_eq!(rects[2], [20.0, 0.0, 50.0, 20.0]);
+	}
+
+	#[test]
+	fn track_sizes_fractions() {
+		let defined =
+			vec![GridTrack::Fixed(100.0), GridTrack::Fraction(1.0), GridTrack::Fraction(3.0)];
+		let sizes = track_sizes(&defined, 3, 500.0, 0.0, Vec::new().into_iter());
+		assert_eq!(sizes, vec![100.0, 100.0, 300.0]);
+
+		let defined = vec![GridTrack::Fraction(1.0), GridTrack::Fraction(1.0)];
+		let sizes = track_sizes(&defined, 2, 110.0, 10.0, Vec::new().into_iter());
+		assert_eq!(sizes, vec![50.0, 50.0]);
+	}
+
+	#[test]
+	fn track_sizes_auto() {
+		let defined = vec![GridTrack::Percent(50.0)];
+		let items =
+			vec![(1, 1, Some(30.0)), (1, 1, Some(20.0)), (0, 2, Some(500.0)), (2, 1, None)];
+		let sizes = track_sizes(&defined, 3, 200.0, 0.0, items.into_iter());
+		assert_eq!(sizes, vec![100.0, 30.0, 0.0]);
+	}
+}
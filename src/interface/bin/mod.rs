@@ -1,19 +1,27 @@
+mod layout;
 pub mod style;
-pub use self::style::{BinPosition, BinStyle, BinVert, Color, ImageEffect};
+mod text;
+pub use self::style::{
+	AlignItems, BinPosition, BinStyle, BinStyleIssue, BinStyleValidation, BinTextSpan, BinVert,
+	Color, FlexDirection, FlexWrap, GridTrack, ImageEffect, JustifyContent,
+};
 
 use super::super::atlas;
 use super::interface::ItfVertInfo;
-use crate::atlas::{Image, ImageData, ImageDims, ImageType, SubImageCacheID};
+use crate::atlas::{
+	AtlasAnimation, AtlasImageID, Image, ImageData, ImageDims, ImageType, SubImageCacheID,
+	SubImageID,
+};
 use crate::image_view::BstImageView;
 use crate::input::*;
 use crate::interface::hook::{BinHook, BinHookData, BinHookFn, BinHookID};
 use crate::interface::interface::scale_verts;
-use crate::{misc, Basalt};
+use crate::{misc, Basalt, BstStyleValidation};
 use arc_swap::ArcSwapAny;
 use ilmenite::*;
 use ordered_float::OrderedFloat;
 use parking_lot::{Mutex, RwLock};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::atomic::{self, AtomicBool};
 use std::sync::{Arc, Barrier, Weak};
 use std::thread;
@@ -147,8 +155,10 @@ pub struct Bin {
 	id: u64,
 	hrchy: ArcSwapAny<Arc<BinHrchy>>,
 	style: ArcSwapAny<Arc<BinStyle>>,
+	own_style: ArcSwapAny<Arc<BinStyle>>,
 	initial: Mutex<bool>,
 	update: AtomicBool,
+	update_colors: AtomicBool,
 	verts: Mutex<Vec<(Vec<ItfVertInfo>, Option<Arc<BstImageView>>, u64)>>,
 	post_update: RwLock<PostUpdate>,
 	on_update: Mutex<Vec<Arc<dyn Fn() + Send + Sync>>>,
@@ -159,6 +169,84 @@ pub struct Bin {
 	hook_ids: Mutex<Vec<BinHookID>>,
 	used_by_basalt: AtomicBool,
 	update_stats: Mutex<BinUpdateStats>,
+	animation: Mutex<BinAnimation>,
+	text_measure: Mutex<Option<(BinTextStyle, [f32; 2])>>,
+	layout_cache: Mutex<Option<BinLayoutCache>>,
+	child_layout: Mutex<Option<BinChildLayout>>,
+}
+
+/// Ancestor data and position from the last update that calculated them. Updates where only
+/// colors or opacity changed reuse these instead of recalculating the layout.
+struct BinLayoutCache {
+	win_size: [f32; 2],
+	scale: f32,
+	ancestors: Vec<(Weak<Bin>, f32, f32, f32, f32)>,
+	tlwh: (f32, f32, f32, f32),
+}
+
+/// Flex or grid layout of a bin's children. The first child updated in an update pass computes
+/// it for all of them, the others use it while the inputs are the same.
+struct BinChildLayout {
+	pass: u64,
+	win_size: Option<[f32; 2]>,
+	position: BinPosition,
+	tlwh: (f32, f32, f32, f32),
+	style: Arc<BinStyle>,
+	children: Vec<(u64, Arc<BinStyle>)>,
+	rects: Vec<[f32; 4]>,
+}
+
+impl BinChildLayout {
+	fn is_for(
+		&self,
+		pass: u64,
+		win_size: Option<[f32; 2]>,
+		position: &BinPosition,
+		tlwh: (f32, f32, f32, f32),
+		style: &Arc<BinStyle>,
+		children: &[(Arc<Bin>, Arc<BinStyle>)],
+	) -> bool {
+		self.pass == pass
+			&& self.win_size == win_size
+			&& self.position == *position
+			&& self.tlwh == tlwh
+			&& Arc::ptr_eq(&self.style, style)
+			&& self.children.len() == children.len()
+			&& self.children.iter().zip(children.iter()).all(
+				|((id, style), (child, child_style))| {
+					*id == child.id() && Arc::ptr_eq(style, child_style)
+				},
+			)
+	}
+}
+
+/// Playback state of an animated background image.
+struct BinAnimation {
+	animation: Option<Arc<AtlasAnimation>>,
+	playing: bool,
+	looping: bool,
+	offset: Duration,
+	resumed: Option<Instant>,
+	next_frame: Option<Instant>,
+}
+
+impl Default for BinAnimation {
+	fn default() -> Self {
+		BinAnimation {
+			animation: None,
+			playing: true,
+			looping: true,
+			offset: Duration::from_secs(0),
+			resumed: None,
+			next_frame: None,
+		}
+	}
+}
+
+impl BinAnimation {
+	fn elapsed(&self) -> Duration {
+		self.offset + self.resumed.map(|resumed| resumed.elapsed()).unwrap_or_default()
+	}
 }
 
 #[derive(Clone, Default, Debug)]
@@ -174,6 +262,10 @@ pub struct PostUpdate {
 	pub z_index: i16,
 	pub pre_bound_min_y: f32,
 	pub pre_bound_max_y: f32,
+	pub pre_bound_min_x: f32,
+	pub pre_bound_max_x: f32,
+	/// Image and sub image ids of the coords used by the background image.
+	back_image: Option<(AtlasImageID, SubImageID)>,
 	text_state: Option<BinTextState>,
 }
 
@@ -182,15 +274,20 @@ struct BinTextState {
 	x: f32,
 	y: f32,
 	style: BinTextStyle,
-	verts: BTreeMap<u64, Vec<ItfVertInfo>>,
+	/// Verts along with their slot in the colors of the text. Each span has two slots, the
+	/// first for its text color and the second for its highlight color.
+	verts: BTreeMap<u64, Vec<(ItfVertInfo, usize)>>,
 	glyphs: Vec<BinGlyphInfo>,
+	/// Image and sub image ids of the coords used by the glyphs.
+	glyph_coords: HashSet<(AtlasImageID, SubImageID)>,
+	/// Bounds of each character relative to the top left inner corner of the bin.
+	chars: Vec<[f32; 4]>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 struct BinTextStyle {
 	scale: f32,
-	text: String,
-	weight: ImtWeight,
+	spans: Vec<text::TextSpan>,
 	body_width: f32,
 	body_height: f32,
 	text_height: f32,
@@ -208,6 +305,14 @@ struct BinGlyphInfo {
 	max_y: f32,
 }
 
+/// Check if a path or url refers to an SVG by its extension.
+fn is_svg(path: &str) -> bool {
+	path.split(|c| c == '?' || c == '#')
+		.next()
+		.map(|path| path.to_lowercase().ends_with(".svg"))
+		.unwrap_or(false)
+}
+
 impl Drop for Bin {
 	fn drop(&mut self) {
 		for hook in self.input_hook_ids.lock().split_off(0) {
@@ -228,8 +333,10 @@ impl Bin {
 			basalt,
 			hrchy: ArcSwapAny::from(Arc::new(BinHrchy::default())),
 			style: ArcSwapAny::new(Arc::new(BinStyle::default())),
+			own_style: ArcSwapAny::new(Arc::new(BinStyle::default())),
 			initial: Mutex::new(true),
 			update: AtomicBool::new(false),
+			update_colors: AtomicBool::new(false),
 			verts: Mutex::new(Vec::new()),
 			post_update: RwLock::new(PostUpdate::default()),
 			on_update: Mutex::new(Vec::new()),
@@ -240,6 +347,10 @@ impl Bin {
 			hook_ids: Mutex::new(Vec::new()),
 			used_by_basalt: AtomicBool::new(false),
 			update_stats: Mutex::new(BinUpdateStats::default()),
+			animation: Mutex::new(BinAnimation::default()),
+			text_measure: Mutex::new(None),
+			layout_cache: Mutex::new(None),
+			child_layout: Mutex::new(None),
 		})
 	}
 
@@ -493,7 +604,7 @@ impl Bin {
 				} = data
 				{
 					let style = match target_wk.upgrade() {
-						Some(bin) => bin.style_copy(),
+						Some(bin) => bin.style(),
 						None => return InputHookRes::Remove,
 					};
 
@@ -650,7 +761,7 @@ impl Bin {
 
 	pub fn fade_out(self: &Arc<Self>, millis: u64) {
 		let bin = self.clone();
-		let start_opacity = self.style_copy().opacity.unwrap_or(1.0);
+		let start_opacity = self.style().opacity.unwrap_or(1.0);
 		let steps = (millis / 10) as i64;
 		let step_size = start_opacity / steps as f32;
 		let mut step_i = 0;
@@ -679,7 +790,7 @@ impl Bin {
 
 	pub fn fade_in(self: &Arc<Self>, millis: u64, target: f32) {
 		let bin = self.clone();
-		let start_opacity = bin.style_copy().opacity.unwrap_or(1.0);
+		let start_opacity = bin.style().opacity.unwrap_or(1.0);
 		let steps = (millis / 10) as i64;
 		let step_size = (target - start_opacity) / steps as f32;
 		let mut step_i = 0;
@@ -735,6 +846,36 @@ impl Bin {
 		}
 	}
 
+	/// Horizontal counterpart of `calc_overflow()`.
+	pub fn calc_overflow_x(self: &Arc<Bin>) -> f32 {
+		let mut min_x = 0.0;
+		let mut max_x = 0.0;
+
+		for child in self.children() {
+			let post = child.post_update.read();
+
+			if post.pre_bound_min_x < min_x {
+				min_x = post.pre_bound_min_x;
+			}
+
+			if post.pre_bound_max_x > max_x {
+				max_x = post.pre_bound_max_x;
+			}
+		}
+
+		let style = self.style();
+		let pad_l = style.pad_l.clone().unwrap_or(0.0);
+		let pad_r = style.pad_r.clone().unwrap_or(0.0);
+		let content_width = max_x - min_x + pad_l + pad_r;
+		let width = self.post_update.read().tri[0];
+
+		if content_width > width {
+			content_width - width
+		} else {
+			0.0
+		}
+	}
+
 	pub fn on_update(&self, func: Arc<dyn Fn() + Send + Sync>) {
 		self.on_update.lock().push(func);
 	}
@@ -758,6 +899,21 @@ impl Bin {
 		self.post_update.read().clone()
 	}
 
+	/// Bounds of each character of the text as of the last update as
+	/// `[min_x, min_y, max_x, max_y]`. Bounds are relative to the top left of the bin, the same
+	/// origin children positioned by `BinPosition::Parent` use, and don't include scrolling.
+	/// Characters are counted across all of `text_spans` when used. An extra zero width bound
+	/// after the last character is included, so this will have one more element than there are
+	/// characters. Empty if the bin had no text.
+	pub fn text_char_bounds(&self) -> Vec<[f32; 4]> {
+		self.post_update
+			.read()
+			.text_state
+			.as_ref()
+			.map(|text_state| text_state.chars.clone())
+			.unwrap_or_default()
+	}
+
 	pub fn id(&self) -> u64 {
 		self.id
 	}
@@ -825,7 +981,7 @@ impl Bin {
 
 				let parent = parent_op.unwrap();
 				let (parent_t, parent_l, parent_w, parent_h) = parent.pos_size_tlwh(win_size_);
-				let parent_style = parent.style_copy();
+				let parent_style = parent.style();
 				let parent_pad_t = parent_style.pad_t.unwrap_or(0.0);
 				let parent_pad_b = parent_style.pad_b.unwrap_or(0.0);
 				let parent_pad_l = parent_style.pad_l.unwrap_or(0.0);
@@ -858,7 +1014,7 @@ impl Bin {
 						continue;
 					}
 
-					let sibling_style = sibling.style_copy();
+					let sibling_style = sibling.style();
 
 					if sibling_style.is_floating_compatible().is_err() {
 						continue;
@@ -886,6 +1042,12 @@ impl Bin {
 
 					sibling_width += sibling_style.width_offset.unwrap_or(0.0);
 					sibling_height += sibling_style.height_offset.unwrap_or(0.0);
+					let (sibling_width, sibling_height) = sibling_style.constrain_size(
+						sibling_width,
+						sibling_height,
+						usable_width,
+						usable_height,
+					);
 
 					siblings.push(Sibling {
 						order: sibling_order,
@@ -970,6 +1132,8 @@ impl Bin {
 
 				width += style.width_offset.unwrap_or(0.0);
 				height += style.height_offset.unwrap_or(0.0);
+				let (width, height) =
+					style.constrain_size(width, height, usable_width, usable_height);
 				let margin_l = style.margin_l.unwrap_or(0.0);
 				let margin_r = style.margin_r.unwrap_or(0.0);
 				let margin_t = style.margin_t.unwrap_or(0.0);
@@ -989,6 +1153,8 @@ impl Bin {
 				let left = parent_l + parent_pad_l + margin_l + current_x;
 				return (top, left, width, height);
 			},
+			BinPosition::Flex | BinPosition::Grid =>
+				return self.pos_size_from_layout(&style, win_size_),
 		};
 
 		let pos_from_t = match style.pos_from_t {
@@ -1029,40 +1195,26 @@ impl Bin {
 		}
 		.map(|v| v + style.pos_from_r_offset.unwrap_or(0.0));
 
-		let from_t = match pos_from_t {
-			Some(from_t) => par_t + from_t,
-			None =>
-				match pos_from_b {
-					Some(from_b) =>
-						match style.height {
-							Some(height) => par_b - from_b - height,
-							None => {
-								println!(
-									"UI Bin Warning! ID: {}, Unable to get position from top, \
-									 position from bottom is specified but no height was \
-									 provied.",
-									self.id
-								);
-								0.0
-							},
-						},
-					None => {
-						println!(
-							"UI Bin Warning! ID: {}, Unable to get position from top, \
-							 position from bottom is non specified.",
-							self.id
-						);
-						0.0
-					},
-				},
-		} + style.pos_from_t_offset.unwrap_or(0.0);
+		let width_auto = style.width_auto == Some(true)
+			&& style.width.is_none()
+			&& style.width_pct.is_none()
+			&& !(pos_from_l.is_some() && pos_from_r.is_some());
+		let height_auto = style.height_auto == Some(true)
+			&& style.height.is_none()
+			&& style.height_pct.is_none()
+			&& !(pos_from_t.is_some() && pos_from_b.is_some());
+		let content_size = if width_auto {
+			Some(self.content_size(&style, None))
+		} else {
+			None
+		};
 
-		let from_l = match pos_from_l {
+		let mut from_l = match pos_from_l {
 			Some(from_l) => from_l + par_l,
 			None =>
 				match pos_from_r {
 					Some(from_r) =>
-						match style.width {
+						match style.width.or(content_size.map(|size| size[0])) {
 							Some(width) => par_r - from_r - width,
 							None => {
 								println!(
@@ -1095,6 +1247,7 @@ impl Bin {
 					None =>
 						match style.width_pct {
 							Some(some) => ((some / 100.0) * (par_r - par_l)) + width_offset,
+							None if width_auto => content_size.unwrap()[0],
 							None => {
 								println!(
 									"UI Bin Warning! ID: {}, Unable to get width. Width must \
@@ -1109,6 +1262,40 @@ impl Bin {
 			}
 		};
 
+		let auto_height = if height_auto {
+			Some(content_size.unwrap_or_else(|| self.content_size(&style, Some(width)))[1])
+		} else {
+			None
+		};
+
+		let mut from_t = match pos_from_t {
+			Some(from_t) => par_t + from_t,
+			None =>
+				match pos_from_b {
+					Some(from_b) =>
+						match style.height.or(auto_height) {
+							Some(height) => par_b - from_b - height,
+							None => {
+								println!(
+									"UI Bin Warning! ID: {}, Unable to get position from top, \
+									 position from bottom is specified but no height was \
+									 provied.",
+									self.id
+								);
+								0.0
+							},
+						},
+					None => {
+						println!(
+							"UI Bin Warning! ID: {}, Unable to get position from top, \
+							 position from bottom is non specified.",
+							self.id
+						);
+						0.0
+					},
+				},
+		} + style.pos_from_t_offset.unwrap_or(0.0);
+
 		let height_offset = style.height_offset.unwrap_or(0.0);
 		let height = {
 			if pos_from_t.is_some() && pos_from_b.is_some() {
@@ -1119,6 +1306,7 @@ impl Bin {
 					None =>
 						match style.height_pct {
 							Some(some) => ((some / 100.0) * (par_b - par_t)) + height_offset,
+							None if height_auto => auto_height.unwrap(),
 							None => {
 								println!(
 									"UI Bin Warning! ID: {}, Unable to get height. Height \
@@ -1133,7 +1321,407 @@ impl Bin {
 			}
 		};
 
-		(from_t, from_l, width, height)
+		let (constrained_w, constrained_h) =
+			style.constrain_size(width, height, par_r - par_l, par_b - par_t);
+
+		// Bins positioned from the right or bottom stay anchored there when constrained.
+		if pos_from_l.is_none() && pos_from_r.is_some() {
+			from_l += width - constrained_w;
+		}
+
+		if pos_from_t.is_none() && pos_from_b.is_some() {
+			from_t += height - constrained_h;
+		}
+
+		(from_t, from_l, constrained_w, constrained_h)
+	}
+
+	/// Position and size of a bin whose parent lays out its children, e.g.
+	/// `BinPosition::Flex` or `BinPosition::Grid`.
+	fn pos_size_from_layout(
+		&self,
+		style: &Arc<BinStyle>,
+		win_size_: Option<[f32; 2]>,
+	) -> (f32, f32, f32, f32) {
+		let position = style.position.clone().unwrap_or_default();
+
+		let is_compatible = |style: &BinStyle| {
+			match position {
+				BinPosition::Grid => style.is_grid_compatible(),
+				_ => style.is_flex_compatible(),
+			}
+		};
+
+		if let Err(e) = is_compatible(style) {
+			println!(
+				"UI Bin Warning! ID: {}, Incompatible 'BinStyle' for 'BinPosition::{:?}': {}",
+				self.id, position, e
+			);
+			return (0.0, 0.0, 0.0, 0.0);
+		}
+
+		let parent = match self.parent() {
+			Some(some) => some,
+			None => {
+				println!(
+					"UI Bin Warning! ID: {}, Incompatible 'BinStyle' for 'BinPosition::{:?}': \
+					 `Bin` must have a parent 'Bin'.",
+					self.id, position
+				);
+				return (0.0, 0.0, 0.0, 0.0);
+			},
+		};
+
+		let (parent_t, parent_l, parent_w, parent_h) = parent.pos_size_tlwh(win_size_);
+		let parent_style = parent.style();
+		let parent_pad_t = parent_style.pad_t.unwrap_or(0.0);
+		let parent_pad_l = parent_style.pad_l.unwrap_or(0.0);
+		let usable_width = parent_w - parent_pad_l - parent_style.pad_r.unwrap_or(0.0);
+		let usable_height = parent_h - parent_pad_t - parent_style.pad_b.unwrap_or(0.0);
+		let mut index_op = None;
+		let mut siblings = Vec::new();
+
+		for sibling in parent.children().into_iter() {
+			if sibling.id() == self.id {
+				index_op = Some(siblings.len());
+				siblings.push((sibling, style.clone()));
+				continue;
+			}
+
+			let sibling_style = sibling.style();
+
+			if is_compatible(&sibling_style).is_err() || sibling_style.hidden == Some(true) {
+				continue;
+			}
+
+			siblings.push((sibling, sibling_style));
+		}
+
+		let index = match index_op {
+			Some(some) => some,
+			None => {
+				println!(
+					"UI Bin Warning! ID: {}, Error computing order for layout. Missing in \
+					 parent children.",
+					self.id
+				);
+				return (0.0, 0.0, 0.0, 0.0);
+			},
+		};
+
+		let pass = self.basalt.interface_ref().update_pass();
+		let parent_tlwh = (parent_t, parent_l, parent_w, parent_h);
+
+		let cached = parent
+			.child_layout
+			.lock()
+			.as_ref()
+			.filter(|layout| {
+				layout.is_for(pass, win_size_, &position, parent_tlwh, &parent_style, &siblings)
+			})
+			.map(|layout| layout.rects[index]);
+
+		let [top, left, width, height] = match cached {
+			Some(some) => some,
+			None => {
+				let rects = match position {
+					BinPosition::Grid => {
+						let items: Vec<_> = siblings
+							.iter()
+							.map(|(sibling, style)| {
+								let mut item = layout::GridItem::from_style(
+									style,
+									usable_width,
+									usable_height,
+								);
+								let [width, height] =
+									sibling.auto_size(style, item.width, item.height);
+								item.width = item.width.or(width);
+								item.height = item.height.or(height);
+								item
+							})
+							.collect();
+						let container = layout::GridContainer::from_style(
+							&parent_style,
+							usable_width,
+							usable_height,
+						);
+						layout::grid(&container, &items)
+					},
+					_ => {
+						let items: Vec<_> = siblings
+							.iter()
+							.map(|(sibling, style)| {
+								let mut item = layout::FlexItem::from_style(
+									style,
+									usable_width,
+									usable_height,
+								);
+								let [width, height] =
+									sibling.auto_size(style, item.width, item.height);
+								item.width = item.width.or(width);
+								item.height = item.height.or(height);
+								item
+							})
+							.collect();
+						let container = layout::FlexContainer::from_style(
+							&parent_style,
+							usable_width,
+							usable_height,
+						);
+						layout::flex(&container, &items)
+					},
+				};
+
+				let rect = rects[index];
+
+				*parent.child_layout.lock() = Some(BinChildLayout {
+					pass,
+					win_size: win_size_,
+					position,
+					tlwh: parent_tlwh,
+					style: parent_style.clone(),
+					children: siblings
+						.iter()
+						.map(|(sibling, style)| (sibling.id(), style.clone()))
+						.collect(),
+					rects,
+				});
+
+				rect
+			},
+		};
+
+		(parent_t + parent_pad_t + top, parent_l + parent_pad_l + left, width, height)
+	}
+
+	/// Width and height from the content for bins using `width_auto` or `height_auto` within a
+	/// layout. `None` where the size isn't automatic.
+	fn auto_size(
+		&self,
+		style: &BinStyle,
+		width: Option<f32>,
+		height: Option<f32>,
+	) -> [Option<f32>; 2] {
+		let width_auto = style.width_auto == Some(true) && width.is_none();
+		let height_auto = style.height_auto == Some(true) && height.is_none();
+
+		if !width_auto && !height_auto {
+			return [None, None];
+		}
+
+		let size = self.content_size(style, width);
+		[Some(size[0]).filter(|_| width_auto), Some(size[1]).filter(|_| height_auto)]
+	}
+
+	/// Size of the bin's text and children including padding. `width` is the width of the bin
+	/// if it is known, otherwise text is only wrapped at
+	/// `max_width`. Children that are positioned or sized from this bin are not included.
+	fn content_size(&self, style: &BinStyle, width: Option<f32>) -> [f32; 2] {
+		let pad_t = style.pad_t.unwrap_or(0.0);
+		let pad_b = style.pad_b.unwrap_or(0.0);
+		let pad_l = style.pad_l.unwrap_or(0.0);
+		let pad_r = style.pad_r.unwrap_or(0.0);
+		let body_width = match width.or(style.max_width) {
+			Some(width) => (width - pad_l - pad_r).max(0.0),
+			None => f32::MAX,
+		};
+
+		let text_size = self.measure_text(style, body_width);
+		let mut content_w = pad_l + text_size[0];
+		let mut content_h = pad_t + text_size[1];
+
+		// Children are measured from their last layout rather than their post update, since the
+		// post update has been scrolled and cut to fit within this bin. Until this bin has been
+		// laid out once its children are not included. Children force an update of this bin
+		// after their own update, so the size will include them then.
+		let origin = self.layout_cache.lock().as_ref().map(|cache| cache.tlwh);
+
+		let (origin_y, origin_x) = match origin {
+			Some((top, left, ..)) => (top, left),
+			None => return [content_w + pad_r, content_h + pad_b],
+		};
+
+		for child in self.children() {
+			let child_style = child.style();
+			let [counts_w, counts_h] = Self::counts_for_content(&child_style);
+
+			if !counts_w && !counts_h {
+				continue;
+			}
+
+			let child_tlwh = child.layout_cache.lock().as_ref().map(|cache| cache.tlwh);
+
+			let (child_t, child_l, child_w, child_h) = match child_tlwh {
+				Some(some) => some,
+				None => continue,
+			};
+
+			if counts_w {
+				let child_r = child_l + child_w + child_style.border_size_r.unwrap_or(0.0);
+				content_w =
+					content_w.max(child_r + child_style.margin_r.unwrap_or(0.0) - origin_x);
+			}
+
+			if counts_h {
+				let child_b = child_t + child_h + child_style.border_size_b.unwrap_or(0.0);
+				content_h =
+					content_h.max(child_b + child_style.margin_b.unwrap_or(0.0) - origin_y);
+			}
+		}
+
+		[content_w + pad_r, content_h + pad_b]
+	}
+
+	/// Whether the width and height of a child with this style are included in the content size
+	/// of its parent. Children that are positioned or sized from the parent are not.
+	fn counts_for_content(style: &BinStyle) -> [bool; 2] {
+		if style.hidden == Some(true)
+			|| style.position.clone().unwrap_or_default() == BinPosition::Window
+		{
+			return [false, false];
+		}
+
+		let grows = style.flex_grow.unwrap_or(0.0) != 0.0;
+
+		[
+			style.width_pct.is_none()
+				&& style.pos_from_l_pct.is_none()
+				&& style.pos_from_r.is_none()
+				&& style.pos_from_r_pct.is_none()
+				&& !grows,
+			style.height_pct.is_none()
+				&& style.pos_from_t_pct.is_none()
+				&& style.pos_from_b.is_none()
+				&& style.pos_from_b_pct.is_none()
+				&& !grows,
+		]
+	}
+
+	/// Size of the bin's text when laid out within `body_width`. The result is kept until the
+	/// text or its style changes.
+	fn measure_text(&self, style: &BinStyle, body_width: f32) -> [f32; 2] {
+		if style.text.is_empty() && style.text_spans.is_empty() {
+			return [0.0, 0.0];
+		}
+
+		let text_style = BinTextStyle {
+			scale: 1.0,
+			spans: self.text_spans(style),
+			body_width,
+			body_height: f32::MAX,
+			text_height: style.text_height.unwrap_or(12.0),
+			line_spacing: style.line_spacing.unwrap_or(0.0),
+			text_wrap: style.text_wrap.clone().unwrap_or(ImtTextWrap::NewLine),
+			vert_align: ImtVertAlign::Top,
+			hori_align: ImtHoriAlign::Left,
+		};
+
+		if let Some((measured_style, size)) = self.text_measure.lock().as_ref() {
+			if *measured_style == text_style {
+				return *size;
+			}
+		}
+
+		let shaped = match text::shape_text(
+			&self.basalt.interface_ref().ilmenite,
+			&text_style.spans,
+			1.0,
+			ImtShapeOpts {
+				body_width: text_style.body_width,
+				body_height: text_style.body_height,
+				text_height: text_style.text_height,
+				line_spacing: text_style.line_spacing,
+				text_wrap: text_style.text_wrap.clone(),
+				vert_align: text_style.vert_align.clone(),
+				hori_align: text_style.hori_align.clone(),
+				..ImtShapeOpts::default()
+			},
+		) {
+			Ok(ok) => ok,
+			Err(e) => {
+				println!(
+					"[Basalt]: Bin ID: {} | Failed to measure text: {} | Text: \"{}\"",
+					self.id,
+					e,
+					text_style.spans.iter().map(|span| span.text.as_str()).collect::<String>()
+				);
+				return [0.0, 0.0];
+			},
+		};
+
+		// Measured from the line boxes and advances of the characters rather than the glyphs,
+		// so that the size doesn't depend on how far the glyphs reach. Whitespace and the
+		// bound after the last character only add to the height.
+		let mut size = [0.0, 0.0];
+		let chars = text_style.spans.iter().flat_map(|span| span.text.chars()).map(Some);
+
+		for (c, bounds) in chars.chain(Some(None)).zip(shaped.chars.iter()) {
+			if let Some(false) = c.map(char::is_whitespace) {
+				size[0] = f32::max(size[0], bounds[2]);
+			}
+
+			size[1] = f32::max(size[1], bounds[3]);
+		}
+
+		*self.text_measure.lock() = Some((text_style, size));
+		size
+	}
+
+	/// The spans of the bin's text with their style resolved. Text without spans is a single
+	/// span using the bin's style.
+	fn text_spans(&self, style: &BinStyle) -> Vec<text::TextSpan> {
+		let interface = self.basalt.interface_ref();
+		let text_height = style.text_height.unwrap_or(12.0);
+
+		let mask = |text: &String| -> String {
+			match style.text_mask {
+				Some(mask) =>
+					text.chars()
+						.map(|c| {
+							match c {
+								'\n' => c,
+								_ => mask,
+							}
+						})
+						.collect(),
+				None => text.clone(),
+			}
+		};
+
+		if style.text_spans.is_empty() {
+			return vec![text::TextSpan {
+				text: mask(&style.text),
+				height: text_height,
+				fonts: interface.font_chain(
+					style.font_family.as_ref(),
+					style.font_weight.as_ref(),
+					style.font_fallback.as_ref(),
+				),
+				underline: false,
+				strikethrough: false,
+				highlight: false,
+			}];
+		}
+
+		style
+			.text_spans
+			.iter()
+			.map(|span| {
+				text::TextSpan {
+					text: mask(&span.text),
+					height: span.height.unwrap_or(text_height),
+					fonts: interface.font_chain(
+						span.font_family.as_ref().or(style.font_family.as_ref()),
+						span.font_weight.as_ref().or(style.font_weight.as_ref()),
+						style.font_fallback.as_ref(),
+					),
+					underline: span.underline,
+					strikethrough: span.strikethrough,
+					highlight: span.highlight.is_some(),
+				}
+			})
+			.collect()
 	}
 
 	pub fn visible(&self) -> bool {
@@ -1142,7 +1730,7 @@ impl Bin {
 
 	pub fn toggle_hidden(&self) {
 		let mut style = self.style_copy();
-		style.hidden = Some(!style.hidden.unwrap_or(false));
+		style.hidden = Some(!self.style().hidden.unwrap_or(false));
 		self.style_update(style);
 	}
 
@@ -1173,7 +1761,111 @@ impl Bin {
 	}
 
 	pub(crate) fn wants_update(&self) -> bool {
-		self.update.load(atomic::Ordering::SeqCst)
+		if self.update.load(atomic::Ordering::SeqCst)
+			|| self.update_colors.load(atomic::Ordering::SeqCst)
+		{
+			return true;
+		}
+
+		match self.animation.lock().next_frame {
+			Some(next_frame) if next_frame <= Instant::now() => true,
+			Some(next_frame) => {
+				self.basalt.interface_ref().odb.wake_at(next_frame);
+				false
+			},
+			None => false,
+		}
+	}
+
+	/// Obtain the coords of the current frame of an animated background image and schedule
+	/// an update for when the next frame is to be shown.
+	fn animation_frame(&self, animation: Arc<AtlasAnimation>) -> atlas::Coords {
+		let mut state = self.animation.lock();
+
+		if !animation.is_animated() {
+			state.animation = None;
+			state.next_frame = None;
+			return self.basalt.atlas_ref().relocated_coords(animation.frames()[0].coords);
+		}
+
+		if state.animation.as_ref().map(|cur| !Arc::ptr_eq(cur, &animation)).unwrap_or(true) {
+			state.offset = Duration::from_secs(0);
+			state.resumed = if state.playing {
+				Some(Instant::now())
+			} else {
+				None
+			};
+			state.animation = Some(animation.clone());
+		}
+
+		let (frame_i, remaining) = animation.frame_at(state.elapsed(), state.looping);
+
+		state.next_frame = match remaining {
+			Some(remaining) if state.playing => {
+				let next_frame = Instant::now() + remaining;
+				self.basalt.interface_ref().odb.wake_at(next_frame);
+				Some(next_frame)
+			},
+			_ => None,
+		};
+
+		self.basalt.atlas_ref().relocated_coords(animation.frames()[frame_i].coords)
+	}
+
+	fn animation_clear(&self) {
+		let mut state = self.animation.lock();
+		state.animation = None;
+		state.next_frame = None;
+	}
+
+	/// Resume playback of an animated background image. Animations play by default.
+	pub fn animation_play(&self) {
+		let mut state = self.animation.lock();
+
+		if !state.playing {
+			state.playing = true;
+			state.resumed = Some(Instant::now());
+			drop(state);
+			self.force_update();
+		}
+	}
+
+	/// Pause playback of an animated background image on its current frame.
+	pub fn animation_pause(&self) {
+		let mut state = self.animation.lock();
+
+		if state.playing {
+			state.offset = state.elapsed();
+			state.playing = false;
+			state.resumed = None;
+			state.next_frame = None;
+			drop(state);
+			self.force_update();
+		}
+	}
+
+	pub fn animation_is_playing(&self) -> bool {
+		self.animation.lock().playing
+	}
+
+	/// Set if an animated background image should start over once it reaches the end.
+	/// Otherwise it will stop on the last frame. Animations loop by default.
+	pub fn animation_set_loop(&self, looping: bool) {
+		self.animation.lock().looping = looping;
+		self.force_update();
+	}
+
+	/// Start an animated background image over from its first frame.
+	pub fn animation_restart(&self) {
+		let mut state = self.animation.lock();
+		state.offset = Duration::from_secs(0);
+		state.resumed = if state.playing {
+			Some(Instant::now())
+		} else {
+			None
+		};
+		drop(state);
+		self.force_update();
 	}
 
 	pub(crate) fn do_update(self: &Arc<Self>, win_size: [f32; 2], scale: f32) {
@@ -1187,7 +1879,9 @@ impl Bin {
 			return;
 		}
 
-		self.update.store(false, atomic::Ordering::SeqCst);
+		let full_update = self.update.swap(false, atomic::Ordering::SeqCst);
+		let colors_only =
+			self.update_colors.swap(false, atomic::Ordering::SeqCst) && !full_update;
 
 		if update_stats {
 			stats.t_upcheck = inst.elapsed();
@@ -1223,14 +1917,26 @@ impl Bin {
 
 		// -- Ancestors Obtain -------------------------------------------------------------- //
 
-		let ancestor_data: Vec<(Arc<Bin>, Arc<BinStyle>, f32, f32, f32, f32)> = self
-			.ancestors()
-			.into_iter()
-			.map(|bin| {
-				let (top, left, width, height) = bin.pos_size_tlwh(Some(scaled_win_size));
-				(bin.clone(), bin.style(), top, left, width, height)
-			})
-			.collect();
+		let (cached_ancestors, cached_tlwh) = match colors_only {
+			true =>
+				match self.cached_layout(win_size, scale) {
+					Some((ancestors, tlwh)) => (Some(ancestors), Some(tlwh)),
+					None => (None, None),
+				},
+			false => (None, None),
+		};
+
+		let ancestor_data: Vec<(Arc<Bin>, Arc<BinStyle>, f32, f32, f32, f32)> =
+			cached_ancestors.unwrap_or_else(|| {
+				self.ancestors()
+					.into_iter()
+					.map(|bin| {
+						let (top, left, width, height) =
+							bin.pos_size_tlwh(Some(scaled_win_size));
+						(bin.clone(), bin.style(), top, left, width, height)
+					})
+					.collect()
+			});
 
 		if update_stats {
 			stats.t_ancestors = inst.elapsed();
@@ -1240,7 +1946,55 @@ impl Bin {
 
 		// -- Position Calculation ---------------------------------------------------------- //
 
-		let (top, left, width, height) = self.pos_size_tlwh(Some(scaled_win_size));
+		let mut layout_changed = false;
+		let mut moved_in_parent = false;
+
+		let (top, left, width, height) = match cached_tlwh {
+			Some(tlwh) => tlwh,
+			None => {
+				let tlwh = self.pos_size_tlwh(Some(scaled_win_size));
+				let mut layout_cache = self.layout_cache.lock();
+				layout_changed = layout_cache.as_ref().map(|cache| cache.tlwh) != Some(tlwh);
+
+				// The parent measures its content from the layout of its children relative to
+				// itself, which doesn't change when a child only moves along with the parent.
+				let (par_t, par_l) = ancestor_data
+					.first()
+					.map(|(_, _, top, left, ..)| (*top, *left))
+					.unwrap_or((0.0, 0.0));
+
+				moved_in_parent = match layout_cache.as_ref() {
+					Some(cache) => {
+						let (prev_par_t, prev_par_l) = cache
+							.ancestors
+							.first()
+							.map(|(_, top, left, ..)| (*top, *left))
+							.unwrap_or((0.0, 0.0));
+
+						cache.tlwh.0 - prev_par_t != tlwh.0 - par_t
+							|| cache.tlwh.1 - prev_par_l != tlwh.1 - par_l
+							|| cache.tlwh.2 != tlwh.2
+							|| cache.tlwh.3 != tlwh.3
+					},
+					None => true,
+				};
+
+				*layout_cache = Some(BinLayoutCache {
+					win_size,
+					scale,
+					ancestors: ancestor_data
+						.iter()
+						.map(|(bin, _, top, left, width, height)| {
+							(Arc::downgrade(bin), *top, *left, *width, *height)
+						})
+						.collect(),
+					tlwh,
+				});
+
+				tlwh
+			},
+		};
+
 		let border_size_t = style.border_size_t.clone().unwrap_or(0.0);
 		let border_size_b = style.border_size_b.clone().unwrap_or(0.0);
 		let border_size_l = style.border_size_l.clone().unwrap_or(0.0);
@@ -1333,6 +2087,9 @@ impl Bin {
 			z_index,
 			pre_bound_min_y: 0.0,
 			pre_bound_max_y: 0.0,
+			pre_bound_min_x: 0.0,
+			pre_bound_max_x: 0.0,
+			back_image: None,
 			text_state: None,
 		};
 
@@ -1344,10 +2101,33 @@ impl Bin {
 
 		// -- Background Image --------------------------------------------------------- //
 
+		// SVGs are rasterized at the size of the bin on screen, so they are rasterized again
+		// when either the bin's size or the interface scale changes.
+		let svg_w = (width * scale).ceil() as u32;
+		let svg_h = (height * scale).ceil() as u32;
+
 		let (back_img, mut back_coords) = match style.back_image.as_ref() {
+			Some(path) if is_svg(path) => {
+				self.animation_clear();
+
+				if svg_w == 0 || svg_h == 0 {
+					(None, atlas::Coords::none())
+				} else {
+					match self.basalt.atlas_ref().load_svg_from_path(path, svg_w, svg_h) {
+						Ok(coords) => (None, coords),
+						Err(e) => {
+							println!(
+								"UI Bin Warning! ID: {}, failed to load svg into atlas {}: {}",
+								self.id, path, e
+							);
+							(None, atlas::Coords::none())
+						},
+					}
+				}
+			},
 			Some(path) =>
-				match self.basalt.atlas_ref().load_image_from_path(path) {
-					Ok(coords) => (None, coords),
+				match self.basalt.atlas_ref().load_animation_from_path(path) {
+					Ok(animation) => (None, self.animation_frame(animation)),
 					Err(e) => {
 						println!(
 							"UI Bin Warning! ID: {}, failed to load image into atlas {}: {}",
@@ -1358,9 +2138,28 @@ impl Bin {
 				},
 			None =>
 				match style.back_image_url.as_ref() {
+					Some(url) if is_svg(url) => {
+						self.animation_clear();
+
+						if svg_w == 0 || svg_h == 0 {
+							(None, atlas::Coords::none())
+						} else {
+							match self.basalt.atlas_ref().load_svg_from_url(url, svg_w, svg_h) {
+								Ok(coords) => (None, coords),
+								Err(e) => {
+									println!(
+										"UI Bin Warning! ID: {}, failed to load svg into \
+										 atlas {}: {}",
+										self.id, url, e
+									);
+									(None, atlas::Coords::none())
+								},
+							}
+						}
+					},
 					Some(url) =>
-						match self.basalt.atlas_ref().load_image_from_url(url) {
-							Ok(coords) => (None, coords),
+						match self.basalt.atlas_ref().load_animation_from_url(url) {
+							Ok(animation) => (None, self.animation_frame(animation)),
 							Err(e) => {
 								println!(
 									"UI Bin Warning! ID: {}, failed to load image into atlas \
@@ -1372,7 +2171,8 @@ impl Bin {
 						},
 					None =>
 						match style.back_image_atlas.clone() {
-							Some(coords) => (None, coords),
+							Some(coords) =>
+								(None, self.basalt.atlas_ref().relocated_coords(coords)),
 							None =>
 								match style.back_image_raw.as_ref() {
 									Some(image) => {
@@ -1396,10 +2196,47 @@ impl Bin {
 				},
 		};
 
+		if style.back_image.is_none() && style.back_image_url.is_none() {
+			self.animation_clear();
+		}
+
 		if back_img.is_some() && back_coords.img_id == 0 {
 			back_coords.img_id = ::std::u64::MAX;
 		}
 
+		if style.back_image_levels.unwrap_or(false)
+			&& back_img.is_none()
+			&& back_coords.img_id != 0
+		{
+			// Levels are generated on the atlas's load threads. Until they are ready the full
+			// image is used and the bin is updated again once they are.
+			let atlas = self.basalt.atlas_ref();
+			let levels = atlas.generate_levels_async(back_coords);
+
+			match levels.poll() {
+				Some(Ok(_)) =>
+					back_coords = atlas.level_coords(back_coords, width * scale, height * scale),
+				Some(Err(e)) =>
+					println!(
+						"UI Bin Warning! ID: {}, failed to generate image levels: {}",
+						self.id, e
+					),
+				None => {
+					let bin_wk = Arc::downgrade(self);
+
+					levels.on_complete(move |result| {
+						if let (Ok(_), Some(bin)) = (result, bin_wk.upgrade()) {
+							bin.force_update();
+						}
+					});
+				},
+			}
+		}
+
+		if back_img.is_none() && back_coords.img_id != 0 {
+			bps.back_image = Some((back_coords.img_id, back_coords.sub_img_id));
+		}
+
 		let back_img_vert_ty = match style.back_srgb_yuv.as_ref() {
 			Some(some) =>
 				match some {
@@ -1652,6 +2489,169 @@ impl Bin {
 					});
 				}
 			}
+
+			if (border_color_t.a > 0.0 && border_size_t > 0.0)
+				|| (border_color_b.a > 0.0 && border_size_b > 0.0)
+				|| (border_color_l.a > 0.0 && border_size_l > 0.0)
+				|| (border_color_r.a > 0.0 && border_size_r > 0.0)
+			{
+				let color_t = border_color_t.as_tuple();
+				let color_b = border_color_b.as_tuple();
+				let color_l = border_color_l.as_tuple();
+				let color_r = border_color_r.as_tuple();
+				let mut border_verts = Vec::new();
+
+				// Straight sides between the corners
+				let sides = [
+					(border_size_t, color_t, [
+						(bps.tri[0] - border_radius_tr, bps.tro[1]),
+						(bps.tli[0] + border_radius_tl, bps.tlo[1]),
+						(bps.tli[0] + border_radius_tl, bps.tli[1]),
+						(bps.tri[0] - border_radius_tr, bps.tri[1]),
+					]),
+					(border_size_b, color_b, [
+						(bps.bri[0] - border_radius_br, bps.bri[1]),
+						(bps.bli[0] + border_radius_bl, bps.bli[1]),
+						(bps.bli[0] + border_radius_bl, bps.blo[1]),
+						(bps.bri[0] - border_radius_br, bps.bro[1]),
+					]),
+					(border_size_l, color_l, [
+						(bps.tli[0], bps.tli[1] + border_radius_tl),
+						(bps.tlo[0], bps.tli[1] + border_radius_tl),
+						(bps.blo[0], bps.bli[1] - border_radius_bl),
+						(bps.bli[0], bps.bli[1] - border_radius_bl),
+					]),
+					(border_size_r, color_r, [
+						(bps.tro[0], bps.tri[1] + border_radius_tr),
+						(bps.tri[0], bps.tri[1] + border_radius_tr),
+						(bps.bri[0], bps.bri[1] - border_radius_br),
+						(bps.bro[0], bps.bri[1] - border_radius_br),
+					]),
+				];
+
+				for (size, color, [p0, p1, p2, p3]) in sides.iter() {
+					if *size > 0.0 && color.3 > 0.0 {
+						for p in [p0, p1, p2, p0, p2, p3].iter() {
+							border_verts.push((**p, *color));
+						}
+					}
+				}
+
+				// Corners follow the curve of the background. The outer curve uses the same
+				// end points offset by the border size of each side.
+				let corners = [
+					(
+						border_radius_tl,
+						(
+							(bps.tli[0], bps.tli[1] + border_radius_tl),
+							(bps.tli[0], bps.tli[1]),
+							(bps.tli[0] + border_radius_tl, bps.tli[1]),
+						),
+						(
+							(bps.tlo[0], bps.tli[1] + border_radius_tl),
+							(bps.tlo[0], bps.tlo[1]),
+							(bps.tli[0] + border_radius_tl, bps.tlo[1]),
+						),
+						(border_size_l, color_l),
+						(border_size_t, color_t),
+					),
+					(
+						border_radius_tr,
+						(
+							(bps.tri[0], bps.tri[1] + border_radius_tr),
+							(bps.tri[0], bps.tri[1]),
+							(bps.tri[0] - border_radius_tr, bps.tri[1]),
+						),
+						(
+							(bps.tro[0], bps.tri[1] + border_radius_tr),
+							(bps.tro[0], bps.tro[1]),
+							(bps.tri[0] - border_radius_tr, bps.tro[1]),
+						),
+						(border_size_r, color_r),
+						(border_size_t, color_t),
+					),
+					(
+						border_radius_bl,
+						(
+							(bps.bli[0], bps.bli[1] - border_radius_bl),
+							(bps.bli[0], bps.bli[1]),
+							(bps.bli[0] + border_radius_bl, bps.bli[1]),
+						),
+						(
+							(bps.blo[0], bps.bli[1] - border_radius_bl),
+							(bps.blo[0], bps.blo[1]),
+							(bps.bli[0] + border_radius_bl, bps.blo[1]),
+						),
+						(border_size_l, color_l),
+						(border_size_b, color_b),
+					),
+					(
+						border_radius_br,
+						(
+							(bps.bri[0], bps.bri[1] - border_radius_br),
+							(bps.bri[0], bps.bri[1]),
+							(bps.bri[0] - border_radius_br, bps.bri[1]),
+						),
+						(
+							(bps.bro[0], bps.bri[1] - border_radius_br),
+							(bps.bro[0], bps.bro[1]),
+							(bps.bri[0] - border_radius_br, bps.bro[1]),
+						),
+						(border_size_r, color_r),
+						(border_size_b, color_b),
+					),
+				];
+
+				for (radius, inner, outer, (size_a, color_a), (size_c, color_c)) in
+					corners.iter()
+				{
+					let draw_a = *size_a > 0.0 && color_a.3 > 0.0;
+					let draw_c = *size_c > 0.0 && color_c.3 > 0.0;
+
+					if !draw_a && !draw_c {
+						continue;
+					}
+
+					if *radius == 0.0 {
+						// Square corner split between the two sides like bins without a radius.
+						if draw_a {
+							border_verts.push((outer.1, *color_a));
+							border_verts.push((outer.0, *color_a));
+							border_verts.push((inner.1, *color_a));
+						}
+
+						if draw_c {
+							border_verts.push((outer.1, *color_c));
+							border_verts.push((inner.1, *color_c));
+							border_verts.push((outer.2, *color_c));
+						}
+					} else {
+						// The color blends from one side to the other along the curve.
+						let segments = curve_ring_segments(*inner, *outer);
+						let steps = segments.len() as f32;
+
+						for (i, ((i0, i1), (o0, o1))) in segments.into_iter().enumerate() {
+							let color0 = lerp_color(*color_a, *color_c, i as f32 / steps);
+							let color1 = lerp_color(*color_a, *color_c, (i + 1) as f32 / steps);
+							border_verts.push((o0, color0));
+							border_verts.push((i0, color0));
+							border_verts.push((i1, color1));
+							border_verts.push((o0, color0));
+							border_verts.push((i1, color1));
+							border_verts.push((o1, color1));
+						}
+					}
+				}
+
+				for ((x, y), color) in border_verts {
+					verts.push(ItfVertInfo {
+						position: (x, y, base_z),
+						coords: (0.0, 0.0),
+						color,
+						ty: 0,
+					});
+				}
+			}
 		} else {
 			if border_color_t.a > 0.0 && border_size_t > 0.0 {
 				// Top Border
@@ -2061,7 +3061,7 @@ impl Bin {
 
 		// -- Text -------------------------------------------------------------------------- //
 
-		if style.text.len() != 0 {
+		if !style.text.is_empty() || !style.text_spans.is_empty() {
 			loop {
 				let pad_t = style.pad_t.clone().unwrap_or(0.0);
 				let pad_b = style.pad_b.clone().unwrap_or(0.0);
@@ -2069,21 +3069,39 @@ impl Bin {
 				let pad_r = style.pad_r.clone().unwrap_or(0.0);
 				let body_width = (bps.tri[0] - bps.tli[0] - pad_l - pad_r) * scale;
 				let body_height = (bps.bli[1] - bps.tli[1] - pad_t - pad_b) * scale;
-				let mut color = style.text_color.clone().unwrap_or(Color::srgb_hex("000000"));
-				color.a *= opacity;
+				let color = style.text_color.clone().unwrap_or(Color::srgb_hex("000000"));
 				let text_height = style.text_height.clone().unwrap_or(12.0);
 				let text_wrap = style.text_wrap.clone().unwrap_or(ImtTextWrap::NewLine);
 				let vert_align = style.text_vert_align.clone().unwrap_or(ImtVertAlign::Top);
 				let hori_align = style.text_hori_align.clone().unwrap_or(ImtHoriAlign::Left);
 				let line_spacing = style.line_spacing.clone().unwrap_or(0.0);
+				let highlight_z = (base_z + content_z) / 2.0;
+				let mut colors = Vec::new();
+
+				if style.text_spans.is_empty() {
+					colors.push(color.clone());
+					colors.push(Color::default());
+				} else {
+					for span in style.text_spans.iter() {
+						colors.push(span.color.clone().unwrap_or_else(|| color.clone()));
+						colors.push(span.highlight.clone().unwrap_or_default());
+					}
+				}
+
+				let colors: Vec<_> = colors
+					.into_iter()
+					.map(|mut color| {
+						color.a *= opacity;
+						color.as_tuple()
+					})
+					.collect();
 
 				let mut text_state = BinTextState {
 					x: bps.tli[0] + pad_l,
 					y: bps.tli[1] + pad_t,
 					style: BinTextStyle {
 						scale,
-						text: style.text.clone(),
-						weight: ImtWeight::Normal,
+						spans: self.text_spans(&style),
 						body_width,
 						body_height,
 						text_height,
@@ -2094,6 +3112,8 @@ impl Bin {
 					},
 					verts: BTreeMap::new(),
 					glyphs: Vec::new(),
+					glyph_coords: HashSet::new(),
+					chars: Vec::new(),
 				};
 
 				if prev_update.text_state.is_some()
@@ -2102,30 +3122,38 @@ impl Bin {
 					let prev_text_state = prev_update.text_state.as_ref().unwrap();
 					let trans_x = text_state.x - prev_text_state.x;
 					let trans_y = text_state.y - prev_text_state.y;
+					text_state.chars = prev_text_state.chars.clone();
+					text_state.glyph_coords = prev_text_state.glyph_coords.clone();
 
 					for (atlas_i, prev_verts) in prev_text_state.verts.iter() {
 						let verts =
 							text_state.verts.entry(*atlas_i).or_insert_with(|| Vec::new());
 
-						for vert in prev_verts {
-							verts.push(ItfVertInfo {
-								position: (
-									vert.position.0 + trans_x,
-									vert.position.1 + trans_y,
-									content_z,
-								),
-								coords: vert.coords.clone(),
-								color: color.as_tuple(),
-								ty: 2,
-							});
+						for (vert, color_i) in prev_verts {
+							verts.push((
+								ItfVertInfo {
+									position: (
+										vert.position.0 + trans_x,
+										vert.position.1 + trans_y,
+										match color_i % 2 {
+											1 => highlight_z,
+											_ => content_z,
+										},
+									),
+									coords: vert.coords.clone(),
+									color: colors[*color_i],
+									ty: vert.ty,
+								},
+								*color_i,
+							));
 						}
 					}
 				} else {
-					let glyphs = match self.basalt.interface_ref().ilmenite.glyphs_for_text(
-						"ABeeZee".into(),
-						ImtWeight::Normal,
-						text_height * scale,
-						Some(ImtShapeOpts {
+					let shaped = match text::shape_text(
+						&self.basalt.interface_ref().ilmenite,
+						&text_state.style.spans,
+						scale,
+						ImtShapeOpts {
 							body_width,
 							body_height,
 							text_height,
@@ -2134,15 +3162,21 @@ impl Bin {
 							vert_align,
 							hori_align,
 							..ImtShapeOpts::default()
-						}),
-						style.text.clone(),
+						},
 					) {
 						Ok(ok) => ok,
 						Err(e) => {
 							println!(
-								"[Basalt]: Bin ID: {} | Failed to render text: {:?} | Text: \
+								"[Basalt]: Bin ID: {} | Failed to render text: {} | Text: \
 								 \"{}\"",
-								self.id, e, style.text
+								self.id,
+								e,
+								text_state
+									.style
+									.spans
+									.iter()
+									.map(|span| span.text.as_str())
+									.collect::<String>()
 							);
 							break;
 						},
@@ -2152,21 +3186,36 @@ impl Bin {
 						stats.t_ilmenite = inst.elapsed();
 					}
 
+					text_state.chars = shaped
+						.chars
+						.iter()
+						.map(|bound| {
+							[
+								(bound[0] / scale) + pad_l,
+								(bound[1] / scale) + pad_t,
+								(bound[2] / scale) + pad_l,
+								(bound[3] / scale) + pad_t,
+							]
+						})
+						.collect();
+
 					let cached_coords = self.basalt.atlas_ref().batch_cache_coords(
-						glyphs
+						shaped
+							.glyphs
 							.iter()
-							.map(|glyph| {
+							.map(|(span_i, glyph)| {
 								SubImageCacheID::Glyph(
 									glyph.family.clone(),
 									glyph.weight.clone(),
 									glyph.index,
-									OrderedFloat::from(text_height),
+									OrderedFloat::from(text_state.style.spans[*span_i].height),
 								)
 							})
 							.collect(),
 					);
 
-					for (glyph, coords_op) in glyphs.into_iter().zip(cached_coords.into_iter())
+					for ((span_i, glyph), coords_op) in
+						shaped.glyphs.into_iter().zip(cached_coords.into_iter())
 					{
 						let coords = if glyph.w == 0 || glyph.h == 0 || glyph.bitmap.is_none() {
 							continue;
@@ -2178,7 +3227,9 @@ impl Bin {
 										glyph.family,
 										glyph.weight,
 										glyph.index,
-										OrderedFloat::from(text_height),
+										OrderedFloat::from(
+											text_state.style.spans[span_i].height,
+										),
 									);
 
 									self.basalt
@@ -2221,50 +3272,31 @@ impl Bin {
 						c_max_x -= glyph.crop_x;
 						c_max_y -= glyph.crop_y;
 
+						text_state.glyph_coords.insert((coords.img_id, coords.sub_img_id));
 						let verts =
 							text_state.verts.entry(coords.img_id).or_insert_with(|| Vec::new());
-
-						verts.push(ItfVertInfo {
-							position: (max_x, min_y, content_z),
-							coords: (c_max_x, c_min_y),
-							color: color.as_tuple(),
-							ty: 2,
-						});
-
-						verts.push(ItfVertInfo {
-							position: (min_x, min_y, content_z),
-							coords: (c_min_x, c_min_y),
-							color: color.as_tuple(),
-							ty: 2,
-						});
-
-						verts.push(ItfVertInfo {
-							position: (min_x, max_y, content_z),
-							coords: (c_min_x, c_max_y),
-							color: color.as_tuple(),
-							ty: 2,
-						});
-
-						verts.push(ItfVertInfo {
-							position: (max_x, min_y, content_z),
-							coords: (c_max_x, c_min_y),
-							color: color.as_tuple(),
-							ty: 2,
-						});
-
-						verts.push(ItfVertInfo {
-							position: (min_x, max_y, content_z),
-							coords: (c_min_x, c_max_y),
-							color: color.as_tuple(),
-							ty: 2,
-						});
-
-						verts.push(ItfVertInfo {
-							position: (max_x, max_y, content_z),
-							coords: (c_max_x, c_max_y),
-							color: color.as_tuple(),
-							ty: 2,
-						});
+						let color_i = span_i * 2;
+
+						for (x, y, c_x, c_y) in [
+							(max_x, min_y, c_max_x, c_min_y),
+							(min_x, min_y, c_min_x, c_min_y),
+							(min_x, max_y, c_min_x, c_max_y),
+							(max_x, min_y, c_max_x, c_min_y),
+							(min_x, max_y, c_min_x, c_max_y),
+							(max_x, max_y, c_max_x, c_max_y),
+						]
+						.iter()
+						{
+							verts.push((
+								ItfVertInfo {
+									position: (*x, *y, content_z),
+									coords: (*c_x, *c_y),
+									color: colors[color_i],
+									ty: 2,
+								},
+								color_i,
+							));
+						}
 
 						text_state.glyphs.push(BinGlyphInfo {
 							min_x,
@@ -2273,10 +3305,48 @@ impl Bin {
 							max_y,
 						});
 					}
+
+					for (span_i, kind, rect) in shaped.rects {
+						let (color_i, z) = match kind {
+							text::TextRectKind::Highlight => ((span_i * 2) + 1, highlight_z),
+							text::TextRectKind::Decoration => (span_i * 2, content_z),
+						};
+
+						let min_x = (rect[0] / scale) + pad_l + bps.tli[0];
+						let min_y = (rect[1] / scale) + pad_t + bps.tli[1];
+						let max_x = (rect[2] / scale) + pad_l + bps.tli[0];
+						let max_y = (rect[3] / scale) + pad_t + bps.tli[1];
+						let verts = text_state.verts.entry(0).or_insert_with(|| Vec::new());
+
+						for (x, y) in [
+							(max_x, min_y),
+							(min_x, min_y),
+							(min_x, max_y),
+							(max_x, min_y),
+							(min_x, max_y),
+							(max_x, max_y),
+						]
+						.iter()
+						{
+							verts.push((
+								ItfVertInfo {
+									position: (*x, *y, z),
+									coords: (0.0, 0.0),
+									color: colors[color_i],
+									ty: 0,
+								},
+								color_i,
+							));
+						}
+					}
 				}
 
 				for (img_id, verts) in text_state.verts.iter() {
-					vert_data.push((verts.clone(), None, *img_id));
+					vert_data.push((
+						verts.iter().map(|(vert, _)| vert.clone()).collect(),
+						None,
+						*img_id,
+					));
 				}
 
 				bps.text_state = Some(text_state);
@@ -2290,7 +3360,7 @@ impl Bin {
 			inst = Instant::now();
 		}
 
-		// -- Get current content height before overflow checks ----------------------------- //
+		// -- Get current content size before overflow checks ------------------------------- //
 
 		for (verts, ..) in &mut vert_data {
 			for vert in verts {
@@ -2301,11 +3371,18 @@ impl Bin {
 				if vert.position.1 > bps.pre_bound_max_y {
 					bps.pre_bound_max_y = vert.position.1;
 				}
+
+				if vert.position.0 < bps.pre_bound_min_x {
+					bps.pre_bound_min_x = vert.position.0;
+				}
+
+				if vert.position.0 > bps.pre_bound_max_x {
+					bps.pre_bound_max_x = vert.position.0;
+				}
 			}
 		}
 
 		// -- Make sure that the verts are within the boundries of all ancestors. ------ //
-		// TODO: Implement horizonal checks
 
 		let mut cut_amt;
 		let mut cut_percent;
@@ -2315,13 +3392,21 @@ impl Bin {
 		let mut coords_max_y;
 		let mut tri_h;
 		let mut img_h;
-
-		for (_check_bin, check_style, check_pft, _check_pfl, _check_w, check_h) in
-			&ancestor_data
+		let mut pos_min_x;
+		let mut pos_max_x;
+		let mut coords_min_x;
+		let mut coords_max_x;
+		let mut tri_w;
+		let mut img_w;
+
+		for (_check_bin, check_style, check_pft, check_pfl, check_w, check_h) in &ancestor_data
 		{
 			let scroll_y = check_style.scroll_y.clone().unwrap_or(0.0);
 			let overflow_y = check_style.overflow_y.clone().unwrap_or(false);
 			let check_b = *check_pft + *check_h;
+			let scroll_x = check_style.scroll_x.clone().unwrap_or(0.0);
+			let overflow_x = check_style.overflow_x.clone().unwrap_or(false);
+			let check_r = *check_pfl + *check_w;
 
 			if !overflow_y {
 				let bps_check_y: Vec<&mut f32> = vec![
@@ -2346,6 +3431,29 @@ impl Bin {
 				}
 			}
 
+			if !overflow_x {
+				let bps_check_x: Vec<&mut f32> = vec![
+					&mut bps.tli[0],
+					&mut bps.tri[0],
+					&mut bps.bli[0],
+					&mut bps.bri[0],
+					&mut bps.tlo[0],
+					&mut bps.tro[0],
+					&mut bps.blo[0],
+					&mut bps.bro[0],
+				];
+
+				for x in bps_check_x {
+					*x -= scroll_x;
+
+					if *x < *check_pfl {
+						*x = *check_pfl;
+					} else if *x > check_r {
+						*x = check_r;
+					}
+				}
+			}
+
 			for (verts, ..) in &mut vert_data {
 				let mut rm_tris: Vec<usize> = Vec::new();
 
@@ -2401,6 +3509,63 @@ impl Bin {
 							}
 						}
 					}
+
+					if rm_tris.last() == Some(&tri_i) {
+						continue;
+					}
+
+					tri[0].position.0 -= scroll_x;
+					tri[1].position.0 -= scroll_x;
+					tri[2].position.0 -= scroll_x;
+
+					if !overflow_x {
+						if (tri[0].position.0 < *check_pfl
+							&& tri[1].position.0 < *check_pfl
+							&& tri[2].position.0 < *check_pfl)
+							|| (tri[0].position.0 > check_r
+								&& tri[1].position.0 > check_r
+								&& tri[2].position.0 > check_r)
+						{
+							rm_tris.push(tri_i);
+						} else {
+							pos_min_x = misc::partial_ord_min3(
+								tri[0].position.0,
+								tri[1].position.0,
+								tri[2].position.0,
+							);
+							pos_max_x = misc::partial_ord_max3(
+								tri[0].position.0,
+								tri[1].position.0,
+								tri[2].position.0,
+							);
+							coords_min_x = misc::partial_ord_min3(
+								tri[0].coords.0,
+								tri[1].coords.0,
+								tri[2].coords.0,
+							);
+							coords_max_x = misc::partial_ord_max3(
+								tri[0].coords.0,
+								tri[1].coords.0,
+								tri[2].coords.0,
+							);
+							tri_w = pos_max_x - pos_min_x;
+							img_w = coords_max_x - coords_min_x;
+
+							for vert in tri {
+								if vert.position.0 < *check_pfl {
+									cut_amt = check_pfl - vert.position.0;
+									cut_percent = cut_amt / tri_w;
+									vert.coords.0 += cut_percent * img_w;
+									vert.position.0 += cut_amt;
+								} else if vert.position.0 > check_r {
+									cut_amt = vert.position.0 - check_r;
+									cut_percent = cut_amt / tri_w;
+									vert.coords.0 -= cut_percent * img_w;
+									vert.position.0 -= cut_amt;
+								}
+							}
+						}
+					}
 				}
 
 				for tri_i in rm_tris.into_iter().rev() {
@@ -2437,6 +3602,26 @@ impl Bin {
 		*self.post_update.write() = bps;
 		*self.last_update.lock() = Instant::now();
 
+		// Bins sized from their content depend on their children and the other way around.
+		// Content is measured from the layout before it is cut to fit within ancestors, so only
+		// changes to the layout are passed on. The parent is flagged rather than updated here,
+		// so it is updated once in the next pass however many of its children changed, and it
+		// only updates its children in turn if its own layout changed.
+		if layout_changed && (style.width_auto == Some(true) || style.height_auto == Some(true))
+		{
+			self.update_children();
+		}
+
+		if moved_in_parent && Self::counts_for_content(&style).contains(&true) {
+			if let Some((parent, parent_style, ..)) = ancestor_data.first() {
+				if parent_style.width_auto == Some(true)
+					|| parent_style.height_auto == Some(true)
+				{
+					parent.force_update();
+				}
+			}
+		}
+
 		if update_stats {
 			stats.t_locks = inst.elapsed();
 			stats.t_total += inst.elapsed();
@@ -2460,11 +3645,71 @@ impl Bin {
 		*self.update_stats.lock() = stats;
 	}
 
+	/// The ancestor data and position from the last layout calculation, if it is still valid
+	/// for the window size and scale. Ancestor styles are current rather than cached, since
+	/// their colors and opacity may have changed without a new layout.
+	fn cached_layout(
+		&self,
+		win_size: [f32; 2],
+		scale: f32,
+	) -> Option<(Vec<(Arc<Bin>, Arc<BinStyle>, f32, f32, f32, f32)>, (f32, f32, f32, f32))> {
+		let cache = self.layout_cache.lock();
+		let cache = cache.as_ref()?;
+
+		if cache.win_size != win_size || cache.scale != scale {
+			return None;
+		}
+
+		let mut ancestor_data = Vec::with_capacity(cache.ancestors.len());
+
+		for (bin, top, left, width, height) in cache.ancestors.iter() {
+			let bin = bin.upgrade()?;
+			let style = bin.style();
+			ancestor_data.push((bin, style, *top, *left, *width, *height));
+		}
+
+		Some((ancestor_data, cache.tlwh))
+	}
+
 	pub fn force_update(&self) {
 		self.update.store(true, atomic::Ordering::SeqCst);
 		self.basalt.interface_ref().odb.unpark();
 	}
 
+	/// Called by the interface after the atlas moved sub images. Bins whose background image
+	/// or glyphs were moved are updated to obtain the new coords. Text is shaped again since
+	/// the text state holds onto the previous glyph coords.
+	pub(crate) fn atlas_relocated(&self, moved: &HashSet<(AtlasImageID, SubImageID)>) {
+		let mut post_update = self.post_update.write();
+		let back_moved = post_update
+			.back_image
+			.map(|back_image| moved.contains(&back_image))
+			.unwrap_or(false);
+		let glyphs_moved = post_update
+			.text_state
+			.as_ref()
+			.map(|text_state| text_state.glyph_coords.iter().any(|key| moved.contains(key)))
+			.unwrap_or(false);
+
+		if glyphs_moved {
+			post_update.text_state = None;
+		}
+
+		drop(post_update);
+
+		if back_moved || glyphs_moved {
+			self.force_update();
+		}
+	}
+
+	/// Called by the interface after a font used by this bin was added again. Text shaped with
+	/// the previous font is discarded.
+	pub(crate) fn fonts_changed(&self) {
+		self.post_update.write().text_state = None;
+		*self.text_measure.lock() = None;
+		self.force_update();
+	}
+
 	pub fn update_children(&self) {
 		self.update_children_priv(false);
 	}
@@ -2479,21 +3724,148 @@ impl Bin {
 		}
 	}
 
+	/// The style used by the bin. This is the style given to the bin with the fields of its
+	/// style classes applied.
 	pub fn style(&self) -> Arc<BinStyle> {
 		self.style.load().clone()
 	}
 
+	/// A copy of the style given to the bin, without the fields of its style classes applied.
 	pub fn style_copy(&self) -> BinStyle {
-		self.style.load().as_ref().clone()
+		self.own_style.load().as_ref().clone()
+	}
+
+	/// Apply the style classes referenced by `own` to it.
+	fn cascade_style(&self, own: &Arc<BinStyle>) -> Arc<BinStyle> {
+		if own.classes.is_empty() {
+			return own.clone();
+		}
+
+		let classes = self.basalt.interface_ref().style_classes(&own.classes);
+
+		if classes.is_empty() {
+			return own.clone();
+		}
+
+		let mut style = own.as_ref().clone();
+
+		for class in classes.iter().rev() {
+			style.inherit(class);
+		}
+
+		Arc::new(style)
+	}
+
+	/// Called by the interface after a style class used by this bin has changed.
+	pub(crate) fn style_classes_changed(&self) {
+		let style = self.cascade_style(&self.own_style.load());
+		self.validate_style(&style);
+		self.style.store(style);
+		self.update_children_priv(true);
 	}
 
 	pub fn style_update(&self, copy: BinStyle) {
-		self.style.store(Arc::new(copy));
+		self.style_update_priv(copy, true);
+	}
+
+	/// Used by basalt's widgets, whose bins are given partial styles that are completed by the
+	/// user or by later updates, so they are not validated.
+	pub(crate) fn style_update_internal(&self, copy: BinStyle) {
+		self.style_update_priv(copy, false);
+	}
+
+	fn style_update_priv(&self, copy: BinStyle, validate: bool) {
+		let own = Arc::new(copy);
+		let style = self.cascade_style(&own);
+
+		if validate {
+			self.validate_style(&style);
+		}
+
+		self.own_style.store(own);
+		self.style.store(style);
 		*self.initial.lock() = false;
 		self.update.store(true, atomic::Ordering::SeqCst);
 		self.basalt.interface_ref().odb.unpark();
 	}
 
+	/// Modify a copy of the style given to the bin and apply it. If the style is unchanged no
+	/// update occurs, and if only colors or opacity changed the previous layout is reused
+	/// instead of being recalculated.
+	///
+	/// ```ignore
+	/// bin.style_modify(|style| {
+	/// 	style.back_color = Some(Color::srgb_hex("ff0000"));
+	/// });
+	/// ```
+	pub fn style_modify<F: FnOnce(&mut BinStyle)>(&self, modify: F) {
+		self.style_modify_priv(modify, true);
+	}
+
+	/// Same as `style_modify` without validation, see `style_update_internal`.
+	pub(crate) fn style_modify_internal<F: FnOnce(&mut BinStyle)>(&self, modify: F) {
+		self.style_modify_priv(modify, false);
+	}
+
+	fn style_modify_priv<F: FnOnce(&mut BinStyle)>(&self, modify: F, validate: bool) {
+		let current = self.own_style.load().clone();
+		let mut copy = current.as_ref().clone();
+		modify(&mut copy);
+		let same_layout = copy.eq_ignoring_colors(&current);
+
+		if same_layout && copy.eq_colors(&current) {
+			return;
+		}
+
+		if *self.initial.lock() || !same_layout {
+			self.style_update_priv(copy, validate);
+			return;
+		}
+
+		// Children multiply in the opacity of their ancestors.
+		if copy.opacity != current.opacity {
+			self.update_children_priv(false);
+		}
+
+		let own = Arc::new(copy);
+		let style = self.cascade_style(&own);
+
+		if validate {
+			self.validate_style(&style);
+		}
+
+		self.own_style.store(own);
+		self.style.store(style);
+		self.update_colors.store(true, atomic::Ordering::SeqCst);
+		self.basalt.interface_ref().odb.unpark();
+	}
+
+	fn validate_style(&self, style: &BinStyle) {
+		let panic = match self.basalt.options_ref().style_validation {
+			BstStyleValidation::Disabled => return,
+			BstStyleValidation::Log => false,
+			BstStyleValidation::Panic => true,
+		};
+
+		let validation = style.validate();
+
+		for warning in &validation.warnings {
+			println!("UI Bin Warning! ID: {}, Style: {}", self.id, warning);
+		}
+
+		if panic && !validation.is_valid() {
+			panic!(
+				"UI Bin ID: {}, Invalid style: {}",
+				self.id,
+				validation.errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ")
+			);
+		}
+
+		for error in &validation.errors {
+			println!("UI Bin Error! ID: {}, Style: {}", self.id, error);
+		}
+	}
+
 	pub fn hidden(self: &Arc<Self>, to: Option<bool>) {
 		let mut copy = self.style_copy();
 		copy.hidden = to;
@@ -2608,6 +3980,45 @@ impl Bin {
 	}
 }
 
+/// Segments of the ring between two curves as pairs of inner and outer line segments. The
+/// amount of segments is determined by the outer curve so that both curves line up.
+fn curve_ring_segments(
+	inner: ((f32, f32), (f32, f32), (f32, f32)),
+	outer: ((f32, f32), (f32, f32), (f32, f32)),
+) -> Vec<(((f32, f32), (f32, f32)), ((f32, f32), (f32, f32)))> {
+	let outer_segments = curve_line_segments(outer.0, outer.1, outer.2);
+	let steps = outer_segments.len() as f32;
+	let (a, b, c) = inner;
+
+	let point = |t: f32| {
+		(
+			((1.0 - t).powi(2) * a.0) + (2.0 * (1.0 - t) * t * b.0) + (t.powi(2) * c.0),
+			((1.0 - t).powi(2) * a.1) + (2.0 * (1.0 - t) * t * b.1) + (t.powi(2) * c.1),
+		)
+	};
+
+	outer_segments
+		.into_iter()
+		.enumerate()
+		.map(|(s, outer_segment)| {
+			((point(s as f32 / steps), point((s + 1) as f32 / steps)), outer_segment)
+		})
+		.collect()
+}
+
+fn lerp_color(
+	a: (f32, f32, f32, f32),
+	b: (f32, f32, f32, f32),
+	t: f32,
+) -> (f32, f32, f32, f32) {
+	(
+		a.0 + ((b.0 - a.0) * t),
+		a.1 + ((b.1 - a.1) * t),
+		a.2 + ((b.2 - a.2) * t),
+		a.3 + ((b.3 - a.3) * t),
+	)
+}
+
 fn curve_line_segments(
 	a: (f32, f32),
 	b: (f32, f32),
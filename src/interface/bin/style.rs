@@ -1,6 +1,7 @@
 use crate::atlas;
 use crate::image_view::BstImageView;
-use ilmenite::{ImtHoriAlign, ImtTextWrap, ImtVertAlign};
+use ilmenite::{ImtHoriAlign, ImtTextWrap, ImtVertAlign, ImtWeight};
+use std::fmt;
 use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -12,6 +13,12 @@ pub enum BinPosition {
 	/// Position will be done from the parent's dimensions
 	/// and other siblings the same type.
 	Floating,
+	/// Position will be done by the parent's flex layout along
+	/// with other siblings of the same type. See `flex_direction`.
+	Flex,
+	/// Position will be done by the parent's grid layout along
+	/// with other siblings of the same type. See `grid_columns`.
+	Grid,
 }
 
 impl Default for BinPosition {
@@ -20,6 +27,65 @@ impl Default for BinPosition {
 	}
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum FlexDirection {
+	/// Children are placed left to right.
+	Row,
+	/// Children are placed right to left.
+	RowReverse,
+	/// Children are placed top to bottom.
+	Column,
+	/// Children are placed bottom to top.
+	ColumnReverse,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FlexWrap {
+	/// Children are kept on a single line and shrink to fit.
+	NoWrap,
+	/// Children that don't fit are moved onto a new line.
+	Wrap,
+	/// Same as `Wrap`, but new lines are added before the previous ones.
+	WrapReverse,
+}
+
+/// Distribution of the remaining space along the main axis.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JustifyContent {
+	Start,
+	End,
+	Center,
+	/// Space is placed between children with none at the edges.
+	SpaceBetween,
+	/// Each child gets equal space on both sides.
+	SpaceAround,
+	/// Space between children and at the edges is equal.
+	SpaceEvenly,
+}
+
+/// Alignment of children along the cross axis.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AlignItems {
+	Start,
+	End,
+	Center,
+	/// Children without a size along the cross axis fill the line.
+	Stretch,
+}
+
+/// Size of a column or row within a grid.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridTrack {
+	/// Size in pixels.
+	Fixed(f32),
+	/// Size as a percent of the parent.
+	Percent(f32),
+	/// Share of the space left over after all other tracks are sized.
+	Fraction(f32),
+	/// Size of the largest child within the track.
+	Auto,
+}
+
 #[derive(Default, Clone)]
 pub struct BinStyle {
 	/// Determines the positioning type
@@ -37,6 +103,9 @@ pub struct BinStyle {
 	/// If set to true bin hook events will be passed to
 	/// children instead of this bin.
 	pub pass_events: Option<bool>,
+	/// Names of style classes added with `Interface::set_style_class()`. Fields not set by
+	/// this style are taken from these classes. Later classes take priority over earlier ones.
+	pub classes: Vec<String>,
 	// Position from Edges
 	pub pos_from_t: Option<f32>,
 	pub pos_from_b: Option<f32>,
@@ -59,6 +128,22 @@ pub struct BinStyle {
 	pub height_pct: Option<f32>,
 	/// Used in conjunction with `height_pct` to provide additional flexibility
 	pub height_offset: Option<f32>,
+	/// Size the width from the text and children when no other width is given. Children
+	/// positioned or sized from this bin are not included. Not supported by `Floating`.
+	pub width_auto: Option<bool>,
+	/// Size the height from the text and children when no other height is given.
+	pub height_auto: Option<bool>,
+	/// Limits the size of the bin regardless of how it is determined. When both an absolute
+	/// and a percent limit are set the stricter of the two is used. Minimums take precedence
+	/// over maximums.
+	pub min_width: Option<f32>,
+	pub min_width_pct: Option<f32>,
+	pub max_width: Option<f32>,
+	pub max_width_pct: Option<f32>,
+	pub min_height: Option<f32>,
+	pub min_height_pct: Option<f32>,
+	pub max_height: Option<f32>,
+	pub max_height_pct: Option<f32>,
 	pub margin_t: Option<f32>,
 	pub margin_b: Option<f32>,
 	pub margin_l: Option<f32>,
@@ -70,9 +155,41 @@ pub struct BinStyle {
 	pub pad_r: Option<f32>,
 	// Scrolling
 	pub scroll_y: Option<f32>,
-	pub scroll_x: Option<f32>, // Not Implemented
+	pub scroll_x: Option<f32>,
 	pub overflow_y: Option<bool>,
-	pub overflow_x: Option<bool>, // Not Implemented
+	pub overflow_x: Option<bool>,
+	// Flex
+	/// Direction of the main axis for children using `BinPosition::Flex`. Defaults to `Row`.
+	pub flex_direction: Option<FlexDirection>,
+	pub flex_wrap: Option<FlexWrap>,
+	pub justify_content: Option<JustifyContent>,
+	/// Defaults to `AlignItems::Stretch`.
+	pub align_items: Option<AlignItems>,
+	/// Space between rows of children.
+	pub row_gap: Option<f32>,
+	/// Space between columns of children.
+	pub column_gap: Option<f32>,
+	/// Share of the free space given to this bin within a flex parent. Defaults to `0.0`.
+	pub flex_grow: Option<f32>,
+	/// Share of the overflow taken from this bin within a flex parent. Defaults to `1.0`.
+	pub flex_shrink: Option<f32>,
+	/// Size along the main axis before growing or shrinking. Defaults to `width` or `height`.
+	pub flex_basis: Option<f32>,
+	/// Overrides the parent's `align_items` for this bin.
+	pub align_self: Option<AlignItems>,
+	// Grid
+	/// Columns for children using `BinPosition::Grid`. Children placed outside of these are
+	/// given `GridTrack::Auto` columns. `row_gap` and `column_gap` also apply to grids.
+	pub grid_columns: Option<Vec<GridTrack>>,
+	/// Rows for children using `BinPosition::Grid`. Rows are added as `GridTrack::Auto` when
+	/// there are more children than cells.
+	pub grid_rows: Option<Vec<GridTrack>>,
+	/// Zero-based column within a grid parent. When unset the next free cell is used.
+	pub grid_column: Option<usize>,
+	/// Zero-based row within a grid parent. When unset the next free cell is used.
+	pub grid_row: Option<usize>,
+	pub grid_column_span: Option<usize>,
+	pub grid_row_span: Option<usize>,
 	// Border
 	pub border_size_t: Option<f32>,
 	pub border_size_b: Option<f32>,
@@ -95,8 +212,17 @@ pub struct BinStyle {
 	pub back_image_raw_coords: Option<atlas::Coords>,
 	pub back_srgb_yuv: Option<bool>,
 	pub back_image_effect: Option<ImageEffect>,
+	/// Generate and use lower resolution levels of the background image when it is shown
+	/// smaller than its size in the atlas. Only applies to images within the atlas.
+	pub back_image_levels: Option<bool>,
 	// Text
 	pub text: String,
+	/// Text made of spans that each have their own style. When set, `text` is not used. The
+	/// spans are laid out together as one paragraph.
+	pub text_spans: Vec<BinTextSpan>,
+	/// Character drawn in place of each character of the text, such as for passwords. The
+	/// text itself is unchanged. Line breaks are kept.
+	pub text_mask: Option<char>,
 	pub text_color: Option<Color>,
 	pub text_height: Option<f32>,
 	pub line_spacing: Option<f32>,
@@ -104,10 +230,759 @@ pub struct BinStyle {
 	pub text_wrap: Option<ImtTextWrap>,
 	pub text_vert_align: Option<ImtVertAlign>,
 	pub text_hori_align: Option<ImtHoriAlign>,
+	/// Family of a font added with `Interface::add_font()`. Defaults to the included font.
+	pub font_family: Option<String>,
+	/// Weight of the font. If the family doesn't have this weight, its normal weight is used.
+	pub font_weight: Option<ImtWeight>,
+	/// Families to use, in order, for characters that `font_family` doesn't have. The included
+	/// font is always tried last.
+	pub font_fallback: Option<Vec<String>>,
 	pub custom_verts: Vec<BinVert>,
 }
 
 impl BinStyle {
+	/// Minimum and maximum width and height from the `min_*` and `max_*` fields. Percentages
+	/// are of the provided parent size.
+	pub(crate) fn size_limits(&self, parent_w: f32, parent_h: f32) -> ([f32; 2], [f32; 2]) {
+		let pct = |pct: Option<f32>, of: f32| pct.map(|pct| (pct / 100.0) * of);
+		let min = |a: Option<f32>, b: Option<f32>| a.into_iter().chain(b).fold(0.0, f32::max);
+		let max = |a: Option<f32>, b: Option<f32>| {
+			a.into_iter().chain(b).fold(f32::INFINITY, f32::min)
+		};
+
+		(
+			[
+				min(self.min_width, pct(self.min_width_pct, parent_w)),
+				min(self.min_height, pct(self.min_height_pct, parent_h)),
+			],
+			[
+				max(self.max_width, pct(self.max_width_pct, parent_w)),
+				max(self.max_height, pct(self.max_height_pct, parent_h)),
+			],
+		)
+	}
+
+	/// Limit a width and height by the `min_*` and `max_*` fields.
+	pub(crate) fn constrain_size(
+		&self,
+		width: f32,
+		height: f32,
+		parent_w: f32,
+		parent_h: f32,
+	) -> (f32, f32) {
+		let (min, max) = self.size_limits(parent_w, parent_h);
+		(width.min(max[0]).max(min[0]), height.min(max[1]).max(min[1]))
+	}
+
+	/// Fill the fields not set in this style from a style class. Classes of the style class
+	/// itself are not followed.
+	pub(crate) fn inherit(&mut self, class: &Self) {
+		let BinStyle {
+			position,
+			z_index,
+			add_z_index,
+			hidden,
+			opacity,
+			pass_events,
+			classes: _,
+			pos_from_t,
+			pos_from_b,
+			pos_from_l,
+			pos_from_r,
+			pos_from_t_pct,
+			pos_from_b_pct,
+			pos_from_l_pct,
+			pos_from_r_pct,
+			pos_from_l_offset,
+			pos_from_t_offset,
+			pos_from_r_offset,
+			pos_from_b_offset,
+			width,
+			width_pct,
+			width_offset,
+			height,
+			height_pct,
+			height_offset,
+			width_auto,
+			height_auto,
+			min_width,
+			min_width_pct,
+			max_width,
+			max_width_pct,
+			min_height,
+			min_height_pct,
+			max_height,
+			max_height_pct,
+			margin_t,
+			margin_b,
+			margin_l,
+			margin_r,
+			pad_t,
+			pad_b,
+			pad_l,
+			pad_r,
+			scroll_y,
+			scroll_x,
+			overflow_y,
+			overflow_x,
+			flex_direction,
+			flex_wrap,
+			justify_content,
+			align_items,
+			row_gap,
+			column_gap,
+			flex_grow,
+			flex_shrink,
+			flex_basis,
+			align_self,
+			grid_columns,
+			grid_rows,
+			grid_column,
+			grid_row,
+			grid_column_span,
+			grid_row_span,
+			border_size_t,
+			border_size_b,
+			border_size_l,
+			border_size_r,
+			border_color_t,
+			border_color_b,
+			border_color_l,
+			border_color_r,
+			border_radius_tl,
+			border_radius_tr,
+			border_radius_bl,
+			border_radius_br,
+			back_color,
+			back_image,
+			back_image_url,
+			back_image_atlas,
+			back_image_raw,
+			back_image_raw_coords,
+			back_srgb_yuv,
+			back_image_effect,
+			back_image_levels,
+			text,
+			text_spans,
+			text_mask,
+			text_color,
+			text_height,
+			line_spacing,
+			line_limit,
+			text_wrap,
+			text_vert_align,
+			text_hori_align,
+			font_family,
+			font_weight,
+			font_fallback,
+			custom_verts,
+		} = class;
+
+		self.position = self.position.take().or_else(|| position.clone());
+		self.z_index = self.z_index.take().or(*z_index);
+		self.add_z_index = self.add_z_index.take().or(*add_z_index);
+		self.hidden = self.hidden.take().or(*hidden);
+		self.opacity = self.opacity.take().or(*opacity);
+		self.pass_events = self.pass_events.take().or(*pass_events);
+		self.pos_from_t = self.pos_from_t.take().or(*pos_from_t);
+		self.pos_from_b = self.pos_from_b.take().or(*pos_from_b);
+		self.pos_from_l = self.pos_from_l.take().or(*pos_from_l);
+		self.pos_from_r = self.pos_from_r.take().or(*pos_from_r);
+		self.pos_from_t_pct = self.pos_from_t_pct.take().or(*pos_from_t_pct);
+		self.pos_from_b_pct = self.pos_from_b_pct.take().or(*pos_from_b_pct);
+		self.pos_from_l_pct = self.pos_from_l_pct.take().or(*pos_from_l_pct);
+		self.pos_from_r_pct = self.pos_from_r_pct.take().or(*pos_from_r_pct);
+		self.pos_from_l_offset = self.pos_from_l_offset.take().or(*pos_from_l_offset);
+		self.pos_from_t_offset = self.pos_from_t_offset.take().or(*pos_from_t_offset);
+		self.pos_from_r_offset = self.pos_from_r_offset.take().or(*pos_from_r_offset);
+		self.pos_from_b_offset = self.pos_from_b_offset.take().or(*pos_from_b_offset);
+		self.width = self.width.take().or(*width);
+		self.width_pct = self.width_pct.take().or(*width_pct);
+		self.width_offset = self.width_offset.take().or(*width_offset);
+		self.height = self.height.take().or(*height);
+		self.height_pct = self.height_pct.take().or(*height_pct);
+		self.height_offset = self.height_offset.take().or(*height_offset);
+		self.width_auto = self.width_auto.take().or(*width_auto);
+		self.height_auto = self.height_auto.take().or(*height_auto);
+		self.min_width = self.min_width.take().or(*min_width);
+		self.min_width_pct = self.min_width_pct.take().or(*min_width_pct);
+		self.max_width = self.max_width.take().or(*max_width);
+		self.max_width_pct = self.max_width_pct.take().or(*max_width_pct);
+		self.min_height = self.min_height.take().or(*min_height);
+		self.min_height_pct = self.min_height_pct.take().or(*min_height_pct);
+		self.max_height = self.max_height.take().or(*max_height);
+		self.max_height_pct = self.max_height_pct.take().or(*max_height_pct);
+		self.margin_t = self.margin_t.take().or(*margin_t);
+		self.margin_b = self.margin_b.take().or(*margin_b);
+		self.margin_l = self.margin_l.take().or(*margin_l);
+		self.margin_r = self.margin_r.take().or(*margin_r);
+		self.pad_t = self.pad_t.take().or(*pad_t);
+		self.pad_b = self.pad_b.take().or(*pad_b);
+		self.pad_l = self.pad_l.take().or(*pad_l);
+		self.pad_r = self.pad_r.take().or(*pad_r);
+		self.scroll_y = self.scroll_y.take().or(*scroll_y);
+		self.scroll_x = self.scroll_x.take().or(*scroll_x);
+		self.overflow_y = self.overflow_y.take().or(*overflow_y);
+		self.overflow_x = self.overflow_x.take().or(*overflow_x);
+		self.flex_direction = self.flex_direction.take().or_else(|| flex_direction.clone());
+		self.flex_wrap = self.flex_wrap.take().or_else(|| flex_wrap.clone());
+		self.justify_content = self.justify_content.take().or_else(|| justify_content.clone());
+		self.align_items = self.align_items.take().or_else(|| align_items.clone());
+		self.row_gap = self.row_gap.take().or(*row_gap);
+		self.column_gap = self.column_gap.take().or(*column_gap);
+		self.flex_grow = self.flex_grow.take().or(*flex_grow);
+		self.flex_shrink = self.flex_shrink.take().or(*flex_shrink);
+		self.flex_basis = self.flex_basis.take().or(*flex_basis);
+		self.align_self = self.align_self.take().or_else(|| align_self.clone());
+		self.grid_columns = self.grid_columns.take().or_else(|| grid_columns.clone());
+		self.grid_rows = self.grid_rows.take().or_else(|| grid_rows.clone());
+		self.grid_column = self.grid_column.take().or(*grid_column);
+		self.grid_row = self.grid_row.take().or(*grid_row);
+		self.grid_column_span = self.grid_column_span.take().or(*grid_column_span);
+		self.grid_row_span = self.grid_row_span.take().or(*grid_row_span);
+		self.border_size_t = self.border_size_t.take().or(*border_size_t);
+		self.border_size_b = self.border_size_b.take().or(*border_size_b);
+		self.border_size_l = self.border_size_l.take().or(*border_size_l);
+		self.border_size_r = self.border_size_r.take().or(*border_size_r);
+		self.border_color_t = self.border_color_t.take().or_else(|| border_color_t.clone());
+		self.border_color_b = self.border_color_b.take().or_else(|| border_color_b.clone());
+		self.border_color_l = self.border_color_l.take().or_else(|| border_color_l.clone());
+		self.border_color_r = self.border_color_r.take().or_else(|| border_color_r.clone());
+		self.border_radius_tl = self.border_radius_tl.take().or(*border_radius_tl);
+		self.border_radius_tr = self.border_radius_tr.take().or(*border_radius_tr);
+		self.border_radius_bl = self.border_radius_bl.take().or(*border_radius_bl);
+		self.border_radius_br = self.border_radius_br.take().or(*border_radius_br);
+		self.back_color = self.back_color.take().or_else(|| back_color.clone());
+		self.back_image = self.back_image.take().or_else(|| back_image.clone());
+		self.back_image_url = self.back_image_url.take().or_else(|| back_image_url.clone());
+		self.back_image_atlas = self.back_image_atlas.take().or(*back_image_atlas);
+		self.back_image_raw = self.back_image_raw.take().or_else(|| back_image_raw.clone());
+		self.back_image_raw_coords =
+			self.back_image_raw_coords.take().or(*back_image_raw_coords);
+		self.back_srgb_yuv = self.back_srgb_yuv.take().or(*back_srgb_yuv);
+		self.back_image_effect =
+			self.back_image_effect.take().or_else(|| back_image_effect.clone());
+		self.back_image_levels = self.back_image_levels.take().or(*back_image_levels);
+		self.text_mask = self.text_mask.take().or(*text_mask);
+		self.text_color = self.text_color.take().or_else(|| text_color.clone());
+		self.text_height = self.text_height.take().or(*text_height);
+		self.line_spacing = self.line_spacing.take().or(*line_spacing);
+		self.line_limit = self.line_limit.take().or(*line_limit);
+		self.text_wrap = self.text_wrap.take().or_else(|| text_wrap.clone());
+		self.text_vert_align = self.text_vert_align.take().or_else(|| text_vert_align.clone());
+		self.text_hori_align = self.text_hori_align.take().or_else(|| text_hori_align.clone());
+		self.font_family = self.font_family.take().or_else(|| font_family.clone());
+		self.font_weight = self.font_weight.take().or_else(|| font_weight.clone());
+		self.font_fallback = self.font_fallback.take().or_else(|| font_fallback.clone());
+
+		// Text and spans replace each other, so a style setting either keeps its own text.
+		if self.text.is_empty() && self.text_spans.is_empty() {
+			self.text = text.clone();
+			self.text_spans = text_spans.clone();
+		}
+
+		if self.custom_verts.is_empty() {
+			self.custom_verts = custom_verts.clone();
+		}
+	}
+
+	/// Check if the colors and opacity of this style, including those of its text spans, are
+	/// the same as `other`. Together with `eq_ignoring_colors` this covers every field.
+	pub fn eq_colors(&self, other: &Self) -> bool {
+		self.opacity == other.opacity
+			&& self.border_color_t == other.border_color_t
+			&& self.border_color_b == other.border_color_b
+			&& self.border_color_l == other.border_color_l
+			&& self.border_color_r == other.border_color_r
+			&& self.back_color == other.back_color
+			&& self.text_color == other.text_color
+			&& self.text_spans.len() == other.text_spans.len()
+			&& self
+				.text_spans
+				.iter()
+				.zip(other.text_spans.iter())
+				.all(|(a, b)| a.color == b.color && a.highlight == b.highlight)
+	}
+
+	/// Check if this style only differs from `other` by its colors and opacity. Bins are able
+	/// to reuse their previous layout when this is the case.
+	pub fn eq_ignoring_colors(&self, other: &Self) -> bool {
+		// Destructured so that fields added in the future have to be considered here.
+		let BinStyle {
+			position,
+			z_index,
+			add_z_index,
+			hidden,
+			opacity: _,
+			pass_events,
+			classes,
+			pos_from_t,
+			pos_from_b,
+			pos_from_l,
+			pos_from_r,
+			pos_from_t_pct,
+			pos_from_b_pct,
+			pos_from_l_pct,
+			pos_from_r_pct,
+			pos_from_l_offset,
+			pos_from_t_offset,
+			pos_from_r_offset,
+			pos_from_b_offset,
+			width,
+			width_pct,
+			width_offset,
+			height,
+			height_pct,
+			height_offset,
+			width_auto,
+			height_auto,
+			min_width,
+			min_width_pct,
+			max_width,
+			max_width_pct,
+			min_height,
+			min_height_pct,
+			max_height,
+			max_height_pct,
+			margin_t,
+			margin_b,
+			margin_l,
+			margin_r,
+			pad_t,
+			pad_b,
+			pad_l,
+			pad_r,
+			scroll_y,
+			scroll_x,
+			overflow_y,
+			overflow_x,
+			flex_direction,
+			flex_wrap,
+			justify_content,
+			align_items,
+			row_gap,
+			column_gap,
+			flex_grow,
+			flex_shrink,
+			flex_basis,
+			align_self,
+			grid_columns,
+			grid_rows,
+			grid_column,
+			grid_row,
+			grid_column_span,
+			grid_row_span,
+			border_size_t,
+			border_size_b,
+			border_size_l,
+			border_size_r,
+			border_color_t: _,
+			border_color_b: _,
+			border_color_l: _,
+			border_color_r: _,
+			border_radius_tl,
+			border_radius_tr,
+			border_radius_bl,
+			border_radius_br,
+			back_color: _,
+			back_image,
+			back_image_url,
+			back_image_atlas,
+			back_image_raw,
+			back_image_raw_coords,
+			back_srgb_yuv,
+			back_image_effect,
+			back_image_levels,
+			text,
+			text_spans,
+			text_mask,
+			text_color: _,
+			text_height,
+			line_spacing,
+			line_limit,
+			text_wrap,
+			text_vert_align,
+			text_hori_align,
+			font_family,
+			font_weight,
+			font_fallback,
+			custom_verts,
+		} = self;
+
+		*position == other.position
+			&& *z_index == other.z_index
+			&& *add_z_index == other.add_z_index
+			&& *hidden == other.hidden
+			&& *pass_events == other.pass_events
+			&& *classes == other.classes
+			&& *pos_from_t == other.pos_from_t
+			&& *pos_from_b == other.pos_from_b
+			&& *pos_from_l == other.pos_from_l
+			&& *pos_from_r == other.pos_from_r
+			&& *pos_from_t_pct == other.pos_from_t_pct
+			&& *pos_from_b_pct == other.pos_from_b_pct
+			&& *pos_from_l_pct == other.pos_from_l_pct
+			&& *pos_from_r_pct == other.pos_from_r_pct
+			&& *pos_from_l_offset == other.pos_from_l_offset
+			&& *pos_from_t_offset == other.pos_from_t_offset
+			&& *pos_from_r_offset == other.pos_from_r_offset
+			&& *pos_from_b_offset == other.pos_from_b_offset
+			&& *width == other.width
+			&& *width_pct == other.width_pct
+			&& *width_offset == other.width_offset
+			&& *height == other.height
+			&& *height_pct == other.height_pct
+			&& *height_offset == other.height_offset
+			&& *width_auto == other.width_auto
+			&& *height_auto == other.height_auto
+			&& *min_width == other.min_width
+			&& *min_width_pct == other.min_width_pct
+			&& *max_width == other.max_width
+			&& *max_width_pct == other.max_width_pct
+			&& *min_height == other.min_height
+			&& *min_height_pct == other.min_height_pct
+			&& *max_height == other.max_height
+			&& *max_height_pct == other.max_height_pct
+			&& *margin_t == other.margin_t
+			&& *margin_b == other.margin_b
+			&& *margin_l == other.margin_l
+			&& *margin_r == other.margin_r
+			&& *pad_t == other.pad_t
+			&& *pad_b == other.pad_b
+			&& *pad_l == other.pad_l
+			&& *pad_r == other.pad_r
+			&& *scroll_y == other.scroll_y
+			&& *scroll_x == other.scroll_x
+			&& *overflow_y == other.overflow_y
+			&& *overflow_x == other.overflow_x
+			&& *flex_direction == other.flex_direction
+			&& *flex_wrap == other.flex_wrap
+			&& *justify_content == other.justify_content
+			&& *align_items == other.align_items
+			&& *row_gap == other.row_gap
+			&& *column_gap == other.column_gap
+			&& *flex_grow == other.flex_grow
+			&& *flex_shrink == other.flex_shrink
+			&& *flex_basis == other.flex_basis
+			&& *align_self == other.align_self
+			&& *grid_columns == other.grid_columns
+			&& *grid_rows == other.grid_rows
+			&& *grid_column == other.grid_column
+			&& *grid_row == other.grid_row
+			&& *grid_column_span == other.grid_column_span
+			&& *grid_row_span == other.grid_row_span
+			&& *border_size_t == other.border_size_t
+			&& *border_size_b == other.border_size_b
+			&& *border_size_l == other.border_size_l
+			&& *border_size_r == other.border_size_r
+			&& *border_radius_tl == other.border_radius_tl
+			&& *border_radius_tr == other.border_radius_tr
+			&& *border_radius_bl == other.border_radius_bl
+			&& *border_radius_br == other.border_radius_br
+			&& *back_image == other.back_image
+			&& *back_image_url == other.back_image_url
+			&& *back_image_atlas == other.back_image_atlas
+			&& match (back_image_raw, &other.back_image_raw) {
+				(Some(a), Some(b)) => Arc::ptr_eq(a, b),
+				(None, None) => true,
+				_ => false,
+			} && *back_image_raw_coords == other.back_image_raw_coords
+			&& *back_srgb_yuv == other.back_srgb_yuv
+			&& *back_image_effect == other.back_image_effect
+			&& *back_image_levels == other.back_image_levels
+			&& *text == other.text
+			&& text_spans.len() == other.text_spans.len()
+			&& text_spans
+				.iter()
+				.zip(other.text_spans.iter())
+				.all(|(a, b)| a.eq_ignoring_colors(b))
+			&& *text_mask == other.text_mask
+			&& *text_height == other.text_height
+			&& *line_spacing == other.line_spacing
+			&& *line_limit == other.line_limit
+			&& *text_wrap == other.text_wrap
+			&& *text_vert_align == other.text_vert_align
+			&& *text_hori_align == other.text_hori_align
+			&& *font_family == other.font_family
+			&& *font_weight == other.font_weight
+			&& *font_fallback == other.font_fallback
+			&& *custom_verts == other.custom_verts
+	}
+
+	/// Check the style for fields that are missing, conflicting, not allowed or out of range
+	/// for its `BinPosition`.
+	pub fn validate(&self) -> BinStyleValidation {
+		let mut validation = BinStyleValidation::default();
+		let position = self.position.clone().unwrap_or_default();
+
+		let pos_from = [
+			("pos_from_t", self.pos_from_t),
+			("pos_from_b", self.pos_from_b),
+			("pos_from_l", self.pos_from_l),
+			("pos_from_r", self.pos_from_r),
+			("pos_from_t_pct", self.pos_from_t_pct),
+			("pos_from_b_pct", self.pos_from_b_pct),
+			("pos_from_l_pct", self.pos_from_l_pct),
+			("pos_from_r_pct", self.pos_from_r_pct),
+		];
+
+		let pos_from_offset = [
+			("pos_from_t_offset", self.pos_from_t_offset),
+			("pos_from_b_offset", self.pos_from_b_offset),
+			("pos_from_l_offset", self.pos_from_l_offset),
+			("pos_from_r_offset", self.pos_from_r_offset),
+		];
+
+		match position {
+			BinPosition::Window | BinPosition::Parent => {
+				AxisFields {
+					from_a: ("pos_from_l", self.pos_from_l),
+					from_a_pct: ("pos_from_l_pct", self.pos_from_l_pct),
+					from_b: ("pos_from_r", self.pos_from_r),
+					from_b_pct: ("pos_from_r_pct", self.pos_from_r_pct),
+					size: ("width", self.width),
+					size_pct: ("width_pct", self.width_pct),
+					size_auto: ("width_auto", self.width_auto),
+				}
+				.validate(&mut validation);
+
+				AxisFields {
+					from_a: ("pos_from_t", self.pos_from_t),
+					from_a_pct: ("pos_from_t_pct", self.pos_from_t_pct),
+					from_b: ("pos_from_b", self.pos_from_b),
+					from_b_pct: ("pos_from_b_pct", self.pos_from_b_pct),
+					size: ("height", self.height),
+					size_pct: ("height_pct", self.height_pct),
+					size_auto: ("height_auto", self.height_auto),
+				}
+				.validate(&mut validation);
+			},
+			BinPosition::Floating => {
+				for (field, value) in pos_from.iter().chain(pos_from_offset.iter()) {
+					if value.is_some() {
+						validation.errors.push(BinStyleIssue::NotAllowed(field));
+					}
+				}
+
+				if self.width.is_none() && self.width_pct.is_none() {
+					validation.errors.push(BinStyleIssue::Missing(vec!["width", "width_pct"]));
+				}
+
+				if self.height.is_none() && self.height_pct.is_none() {
+					validation
+						.errors
+						.push(BinStyleIssue::Missing(vec!["height", "height_pct"]));
+				}
+
+				if self.width_auto.is_some() {
+					validation.warnings.push(BinStyleIssue::Ignored("width_auto"));
+				}
+
+				if self.height_auto.is_some() {
+					validation.warnings.push(BinStyleIssue::Ignored("height_auto"));
+				}
+			},
+			BinPosition::Flex | BinPosition::Grid => {
+				for (field, value) in pos_from.iter() {
+					if value.is_some() {
+						validation.errors.push(BinStyleIssue::NotAllowed(field));
+					}
+				}
+
+				for (field, value) in pos_from_offset.iter() {
+					if value.is_some() {
+						validation.warnings.push(BinStyleIssue::Ignored(field));
+					}
+				}
+			},
+		}
+
+		if position != BinPosition::Flex {
+			for (field, is_some) in [
+				("flex_grow", self.flex_grow.is_some()),
+				("flex_shrink", self.flex_shrink.is_some()),
+				("flex_basis", self.flex_basis.is_some()),
+				("align_self", self.align_self.is_some()),
+			]
+			.iter()
+			{
+				if *is_some {
+					validation.warnings.push(BinStyleIssue::Ignored(field));
+				}
+			}
+		}
+
+		if position != BinPosition::Grid {
+			for (field, is_some) in [
+				("grid_column", self.grid_column.is_some()),
+				("grid_row", self.grid_row.is_some()),
+				("grid_column_span", self.grid_column_span.is_some()),
+				("grid_row_span", self.grid_row_span.is_some()),
+			]
+			.iter()
+			{
+				if *is_some {
+					validation.warnings.push(BinStyleIssue::Ignored(field));
+				}
+			}
+		}
+
+		if let Some(opacity) = self.opacity {
+			if !(0.0..=1.0).contains(&opacity) {
+				validation.errors.push(BinStyleIssue::InvalidValue(
+					"opacity",
+					format!("{} is not within 0.0 to 1.0", opacity),
+				));
+			}
+		}
+
+		for (field, value) in [
+			("width", self.width),
+			("height", self.height),
+			("min_width", self.min_width),
+			("max_width", self.max_width),
+			("min_height", self.min_height),
+			("max_height", self.max_height),
+			("pad_t", self.pad_t),
+			("pad_b", self.pad_b),
+			("pad_l", self.pad_l),
+			("pad_r", self.pad_r),
+			("border_size_t", self.border_size_t),
+			("border_size_b", self.border_size_b),
+			("border_size_l", self.border_size_l),
+			("border_size_r", self.border_size_r),
+			("border_radius_tl", self.border_radius_tl),
+			("border_radius_tr", self.border_radius_tr),
+			("border_radius_bl", self.border_radius_bl),
+			("border_radius_br", self.border_radius_br),
+			("row_gap", self.row_gap),
+			("column_gap", self.column_gap),
+			("flex_grow", self.flex_grow),
+			("flex_shrink", self.flex_shrink),
+			("flex_basis", self.flex_basis),
+		]
+		.iter()
+		{
+			if let Some(value) = value {
+				if *value < 0.0 {
+					validation.errors.push(BinStyleIssue::InvalidValue(
+						field,
+						format!("{} is negative", value),
+					));
+				}
+			}
+		}
+
+		if let Some(text_height) = self.text_height {
+			if text_height <= 0.0 {
+				validation.errors.push(BinStyleIssue::InvalidValue(
+					"text_height",
+					format!("{} is not greater than zero", text_height),
+				));
+			}
+		}
+
+		if !self.text.is_empty() && !self.text_spans.is_empty() {
+			validation.warnings.push(BinStyleIssue::Conflict(vec!["text", "text_spans"]));
+		}
+
+		if let Some(mask) = self.text_mask {
+			if mask.is_control() {
+				validation.errors.push(BinStyleIssue::InvalidValue(
+					"text_mask",
+					format!("{:?} is a control character", mask),
+				));
+			}
+		}
+
+		for (i, span) in self.text_spans.iter().enumerate() {
+			if let Some(height) = span.height {
+				if height <= 0.0 {
+					validation.errors.push(BinStyleIssue::InvalidValue(
+						"text_spans",
+						format!("height of span {}, {}, is not greater than zero", i, height),
+					));
+				}
+			}
+		}
+
+		for (field, value) in
+			[("grid_column_span", self.grid_column_span), ("grid_row_span", self.grid_row_span)]
+				.iter()
+		{
+			if *value == Some(0) {
+				validation.errors.push(BinStyleIssue::InvalidValue(
+					field,
+					format!("span must be at least 1"),
+				));
+			}
+		}
+
+		for (min, max) in [
+			(("min_width", self.min_width), ("max_width", self.max_width)),
+			(("min_height", self.min_height), ("max_height", self.max_height)),
+			(("min_width_pct", self.min_width_pct), ("max_width_pct", self.max_width_pct)),
+			(("min_height_pct", self.min_height_pct), ("max_height_pct", self.max_height_pct)),
+		]
+		.iter()
+		{
+			if let (Some(min_v), Some(max_v)) = (min.1, max.1) {
+				if min_v > max_v {
+					validation.warnings.push(BinStyleIssue::Conflict(vec![min.0, max.0]));
+				}
+			}
+		}
+
+		let back_images: Vec<&'static str> = [
+			("back_image", self.back_image.is_some()),
+			("back_image_url", self.back_image_url.is_some()),
+			("back_image_atlas", self.back_image_atlas.is_some()),
+			("back_image_raw", self.back_image_raw.is_some()),
+		]
+		.iter()
+		.filter(|(_, is_some)| *is_some)
+		.map(|(field, _)| *field)
+		.collect();
+
+		if back_images.len() > 1 {
+			validation.warnings.push(BinStyleIssue::Conflict(back_images));
+		}
+
+		validation
+	}
+
+	pub fn is_flex_compatible(&self) -> Result<(), String> {
+		if self.position != Some(BinPosition::Flex) {
+			Err(format!("'position' must be 'BinPosition::Flex'."))
+		} else {
+			self.is_layout_compatible()
+		}
+	}
+
+	pub fn is_grid_compatible(&self) -> Result<(), String> {
+		if self.position != Some(BinPosition::Grid) {
+			Err(format!("'position' must be 'BinPosition::Grid'."))
+		} else {
+			self.is_layout_compatible()
+		}
+	}
+
+	fn is_layout_compatible(&self) -> Result<(), String> {
+		if self.pos_from_t.is_some()
+			|| self.pos_from_b.is_some()
+			|| self.pos_from_l.is_some()
+			|| self.pos_from_r.is_some()
+			|| self.pos_from_t_pct.is_some()
+			|| self.pos_from_b_pct.is_some()
+			|| self.pos_from_l_pct.is_some()
+			|| self.pos_from_r_pct.is_some()
+		{
+			Err(format!("'pos_from_*' fields are not allowed, position is set by the parent."))
+		} else {
+			Ok(())
+		}
+	}
+
 	pub fn is_floating_compatible(&self) -> Result<(), String> {
 		if self.position != Some(BinPosition::Floating) {
 			Err(format!("'position' must be 'BinPosition::Floating'."))
@@ -145,7 +1020,116 @@ impl BinStyle {
 	}
 }
 
-#[derive(Clone, Debug)]
+impl PartialEq for BinStyle {
+	fn eq(&self, other: &Self) -> bool {
+		self.eq_colors(other) && self.eq_ignoring_colors(other)
+	}
+}
+
+/// A problem with a `BinStyle` found by `BinStyle::validate()`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BinStyleIssue {
+	/// None of these fields are set, but one of them is needed.
+	Missing(Vec<&'static str>),
+	/// These fields are set together, but not all of them will be used.
+	Conflict(Vec<&'static str>),
+	/// This field is not allowed with the style's `BinPosition`.
+	NotAllowed(&'static str),
+	/// This field has no effect with the style's `BinPosition`.
+	Ignored(&'static str),
+	/// This field has a value outside of what is valid.
+	InvalidValue(&'static str, String),
+}
+
+impl fmt::Display for BinStyleIssue {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			BinStyleIssue::Missing(fields) =>
+				write!(f, "one of '{}' must be set", fields.join("', '")),
+			BinStyleIssue::Conflict(fields) =>
+				write!(f, "'{}' conflict, not all will be used", fields.join("', '")),
+			BinStyleIssue::NotAllowed(field) =>
+				write!(f, "'{}' is not allowed with this position", field),
+			BinStyleIssue::Ignored(field) => write!(f, "'{}' has no effect", field),
+			BinStyleIssue::InvalidValue(field, desc) =>
+				write!(f, "'{}' is invalid: {}", field, desc),
+		}
+	}
+}
+
+/// Result of `BinStyle::validate()`. Errors prevent the bin from being positioned or drawn
+/// as intended. Warnings are for fields that are resolved or ignored silently.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BinStyleValidation {
+	pub errors: Vec<BinStyleIssue>,
+	pub warnings: Vec<BinStyleIssue>,
+}
+
+impl BinStyleValidation {
+	pub fn is_valid(&self) -> bool {
+		self.errors.is_empty()
+	}
+
+	/// There are no errors or warnings.
+	pub fn is_clean(&self) -> bool {
+		self.errors.is_empty() && self.warnings.is_empty()
+	}
+}
+
+/// The fields used to position and size a bin along one axis.
+struct AxisFields {
+	from_a: (&'static str, Option<f32>),
+	from_a_pct: (&'static str, Option<f32>),
+	from_b: (&'static str, Option<f32>),
+	from_b_pct: (&'static str, Option<f32>),
+	size: (&'static str, Option<f32>),
+	size_pct: (&'static str, Option<f32>),
+	size_auto: (&'static str, Option<bool>),
+}
+
+impl AxisFields {
+	fn validate(&self, validation: &mut BinStyleValidation) {
+		for (abs, pct) in [
+			(self.from_a, self.from_a_pct),
+			(self.from_b, self.from_b_pct),
+			(self.size, self.size_pct),
+		]
+		.iter()
+		{
+			if abs.1.is_some() && pct.1.is_some() {
+				validation.warnings.push(BinStyleIssue::Conflict(vec![abs.0, pct.0]));
+			}
+		}
+
+		let has_a = self.from_a.1.is_some() || self.from_a_pct.1.is_some();
+		let has_b = self.from_b.1.is_some() || self.from_b_pct.1.is_some();
+		let has_size = self.size.1.is_some() || self.size_pct.1.is_some();
+		let size_auto = self.size_auto.1 == Some(true);
+
+		if has_a && has_b {
+			if has_size || size_auto {
+				validation.warnings.push(BinStyleIssue::Conflict(vec![
+					self.from_a.0,
+					self.from_b.0,
+					self.size.0,
+				]));
+			}
+		} else if !has_a && !has_b {
+			validation.errors.push(BinStyleIssue::Missing(vec![self.from_a.0, self.from_b.0]));
+		} else if !has_size && !size_auto {
+			validation.errors.push(BinStyleIssue::Missing(vec![
+				self.size.0,
+				self.size_pct.0,
+				self.size_auto.0,
+			]));
+		} else if has_b && self.size.1.is_none() && !size_auto {
+			// Positioning from the far edge needs the size before percentages are resolved.
+			validation.errors.push(BinStyleIssue::Missing(vec![self.size.0, self.size_auto.0]));
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum ImageEffect {
 	BackColorAdd,
 	BackColorBehind,
@@ -168,6 +1152,35 @@ impl ImageEffect {
 	}
 }
 
+/// A part of a bin's text with its own style, used in `BinStyle::text_spans`. Fields that are
+/// `None` use the value from the bin's style.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct BinTextSpan {
+	pub text: String,
+	pub color: Option<Color>,
+	pub height: Option<f32>,
+	pub font_family: Option<String>,
+	pub font_weight: Option<ImtWeight>,
+	pub underline: bool,
+	pub strikethrough: bool,
+	/// Color drawn behind the span.
+	pub highlight: Option<Color>,
+}
+
+impl BinTextSpan {
+	/// Check if this span only differs from `other` by its colors. Whether the span is
+	/// highlighted is not considered a color.
+	pub fn eq_ignoring_colors(&self, other: &Self) -> bool {
+		self.text == other.text
+			&& self.height == other.height
+			&& self.font_family == other.font_family
+			&& self.font_weight == other.font_weight
+			&& self.underline == other.underline
+			&& self.strikethrough == other.strikethrough
+			&& self.highlight.is_some() == other.highlight.is_some()
+	}
+}
+
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct BinVert {
 	pub position: (f32, f32, i16),
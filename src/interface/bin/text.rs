@@ -0,0 +1,659 @@
+use crate::interface::font::LoadedFont;
+use ilmenite::*;
+
+/// A run of a bin's text with its style resolved from the bin's style.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct TextSpan {
+	pub text: String,
+	/// Text height before scaling.
+	pub height: f32,
+	/// Fonts in order of preference. Each character uses the first font that has it.
+	pub fonts: Vec<LoadedFont>,
+	pub underline: bool,
+	pub strikethrough: bool,
+	pub highlight: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum TextRectKind {
+	/// Drawn behind the glyphs with the span's highlight color.
+	Highlight,
+	/// Underline or strikethrough drawn with the span's text color.
+	Decoration,
+}
+
+/// Text shaped and laid out within the body. Positions are scaled and relative to the top left
+/// of the body.
+pub(super) struct ShapedText {
+	/// Glyphs along with the index of their span.
+	pub glyphs: Vec<(usize, ImtGlyph)>,
+	/// Rectangles as `[min_x, min_y, max_x, max_y]` along with the index of their span.
+	pub rects: Vec<(usize, TextRectKind, [f32; 4])>,
+	/// Bounds of each character across all spans as `[min_x, min_y, max_x, max_y]`, followed
+	/// by a zero width bound after the last character.
+	pub chars: Vec<[f32; 4]>,
+}
+
+impl TextSpan {
+	fn decorated(&self) -> bool {
+		self.underline || self.strikethrough || self.highlight
+	}
+}
+
+/// Shape spans of text as a single paragraph. A single undecorated span that only needs one
+/// font is shaped by ilmenite as a whole and its character bounds are taken from the glyphs.
+/// Otherwise each run of characters sharing a span and font is shaped on its own and the runs
+/// are laid out here.
+pub(super) fn shape_text(
+	ilmenite: &Ilmenite,
+	spans: &[TextSpan],
+	scale: f32,
+	opts: ImtShapeOpts,
+) -> Result<ShapedText, String> {
+	let fonts: Vec<Vec<usize>> = spans.iter().map(fonts_for_chars).collect();
+
+	if let ([span], [span_fonts]) = (spans, fonts.as_slice()) {
+		let single = match span_fonts.first() {
+			Some(first) if span_fonts.iter().all(|font| font == first) => Some(*first),
+			Some(_) => None,
+			None => Some(0),
+		};
+
+		// Empty text is left to `layout_runs` so that it still has the bound for a caret.
+		if let (Some(font), false, false) = (single, span.decorated(), span.text.is_empty()) {
+			let glyphs = ilmenite
+				.glyphs_for_text(
+					span.fonts[font].family.clone(),
+					span.fonts[font].weight.clone(),
+					span.height * scale,
+					Some(ImtShapeOpts {
+						body_width: opts.body_width,
+						body_height: opts.body_height,
+						text_height: span.height,
+						line_spacing: opts.line_spacing,
+						text_wrap: opts.text_wrap.clone(),
+						vert_align: opts.vert_align.clone(),
+						hori_align: opts.hori_align.clone(),
+						..ImtShapeOpts::default()
+					}),
+					span.text.clone(),
+				)
+				.map_err(|e| format!("{:?}", e))?;
+
+			let chars = glyph_char_bounds(span, &span.fonts[font], &glyphs, scale, &opts)
+				.unwrap_or_else(|| char_bounds(&layout_lines(spans, &fonts, scale, &opts)));
+
+			return Ok(ShapedText {
+				glyphs: glyphs.into_iter().map(|glyph| (0, glyph)).collect(),
+				rects: Vec::new(),
+				chars,
+			});
+		}
+	}
+
+	layout_runs(ilmenite, spans, &fonts, scale, opts)
+}
+
+/// Index into the span's fonts of the font used for each character. Whitespace keeps the font
+/// of the previous character so that it doesn't split runs. Characters no font has use the
+/// first font.
+fn fonts_for_chars(span: &TextSpan) -> Vec<usize> {
+	let mut current = 0;
+
+	span.text
+		.chars()
+		.map(|c| {
+			if !c.is_whitespace() && !c.is_control() {
+				current =
+					span.fonts.iter().position(|font| font.metrics.has_char(c)).unwrap_or(0);
+			}
+
+			current
+		})
+		.collect()
+}
+
+/// A character placed by `layout_lines`.
+struct LayoutChar {
+	/// Index of the character across all spans.
+	index: usize,
+	span: usize,
+	font: usize,
+	c: char,
+	advance: f32,
+	height: f32,
+	ascender: f32,
+}
+
+/// A line placed by `layout_lines`.
+struct LayoutLine {
+	chars: Vec<LayoutChar>,
+	x: f32,
+	y: f32,
+	height: f32,
+	ascender: f32,
+}
+
+/// Break the spans into lines and place the lines within the body. Lines only break at the
+/// body's width with `ImtTextWrap::NewLine`.
+fn layout_lines(
+	spans: &[TextSpan],
+	fonts: &[Vec<usize>],
+	scale: f32,
+	opts: &ImtShapeOpts,
+) -> Vec<LayoutLine> {
+	let wrap = opts.text_wrap == ImtTextWrap::NewLine;
+	let mut lines: Vec<Vec<LayoutChar>> = vec![Vec::new()];
+	let mut word: Vec<LayoutChar> = Vec::new();
+	let mut index = 0;
+
+	for (span_i, span) in spans.iter().enumerate() {
+		let height = span.height * scale;
+		let chars: Vec<(char, usize)> =
+			span.text.chars().zip(fonts[span_i].iter().cloned()).collect();
+
+		for (i, &(c, font)) in chars.iter().enumerate() {
+			let metrics = &span.fonts[font].metrics;
+
+			// Runs are shaped on their own, so only characters within a run are kerned.
+			let kerning = match chars.get(i + 1) {
+				Some(&(next, next_font)) if next_font == font => metrics.kerning(c, next),
+				_ => 0.0,
+			};
+
+			word.push(LayoutChar {
+				index,
+				span: span_i,
+				font,
+				c,
+				advance: match c {
+					'\n' => 0.0,
+					_ => (metrics.advance(c) + kerning) * height,
+				},
+				height,
+				ascender: metrics.ascender() * height,
+			});
+
+			index += 1;
+
+			if c.is_whitespace() {
+				push_word(&mut lines, &mut word, opts.body_width, wrap);
+			}
+
+			if c == '\n' {
+				lines.push(Vec::new());
+			}
+		}
+	}
+
+	push_word(&mut lines, &mut word, opts.body_width, wrap);
+
+	// Each line is as tall as its tallest character. Empty lines use the bin's text height.
+	let line_spacing = opts.line_spacing * scale;
+	let line_heights: Vec<(f32, f32)> = lines
+		.iter()
+		.map(|line| {
+			line.iter().fold((0.0, 0.0), |(height, ascender): (f32, f32), lc| {
+				(height.max(lc.height), ascender.max(lc.ascender))
+			})
+		})
+		.map(|(height, ascender)| {
+			match height > 0.0 {
+				true => (height, ascender),
+				false => (opts.text_height * scale, opts.text_height * scale),
+			}
+		})
+		.collect();
+
+	let text_h = line_heights.iter().map(|(height, _)| *height + line_spacing).sum::<f32>()
+		- line_spacing;
+
+	let mut y = match opts.vert_align {
+		ImtVertAlign::Top => 0.0,
+		ImtVertAlign::Center => (opts.body_height - text_h) / 2.0,
+		_ => opts.body_height - text_h,
+	};
+
+	lines
+		.into_iter()
+		.zip(line_heights)
+		.map(|(chars, (height, ascender))| {
+			let line_w = line_width(&chars);
+
+			let x = match opts.hori_align {
+				ImtHoriAlign::Left => 0.0,
+				ImtHoriAlign::Center => (opts.body_width - line_w) / 2.0,
+				_ => opts.body_width - line_w,
+			};
+
+			// Lines that overflow are shifted so that their end stays within the body.
+			let x = match opts.text_wrap {
+				ImtTextWrap::Shift if line_w > opts.body_width => opts.body_width - line_w,
+				_ => x,
+			};
+
+			let line = LayoutLine {
+				chars,
+				x,
+				y,
+				height,
+				ascender,
+			};
+
+			y += height + line_spacing;
+			line
+		})
+		.collect()
+}
+
+/// Bounds of each character of the lines followed by the bound after the last character.
+fn char_bounds(lines: &[LayoutLine]) -> Vec<[f32; 4]> {
+	let mut bounds =
+		Vec::with_capacity(lines.iter().map(|line| line.chars.len()).sum::<usize>() + 1);
+
+	for line in lines.iter() {
+		let mut x = line.x;
+
+		for lc in line.chars.iter() {
+			bounds.push([x, line.y, x + lc.advance, line.y + line.height]);
+			x += lc.advance;
+		}
+	}
+
+	if let Some(line) = lines.last() {
+		let x = line.x + line.chars.iter().map(|lc| lc.advance).sum::<f32>();
+		bounds.push([x, line.y, x, line.y + line.height]);
+	}
+
+	bounds
+}
+
+/// A line of characters placed by ilmenite, see `glyph_char_bounds`.
+#[derive(Default)]
+struct GlyphLine {
+	/// Each character with its advance and where its glyph was placed if it has one.
+	chars: Vec<(char, f32, Option<f32>)>,
+	baseline: Option<f32>,
+}
+
+/// Bounds of each character of a span shaped by ilmenite as a whole followed by the bound after
+/// the last character. Characters are matched to their glyphs in order. A character without a
+/// glyph of its own follows the previous character. A line starts at a new line character or
+/// where the glyphs move back or down to another line. `None` if no glyph has an outline to
+/// find the lines from.
+fn glyph_char_bounds(
+	span: &TextSpan,
+	font: &LoadedFont,
+	glyphs: &[ImtGlyph],
+	scale: f32,
+	opts: &ImtShapeOpts,
+) -> Option<Vec<[f32; 4]>> {
+	let metrics = &font.metrics;
+	let height = span.height * scale;
+	let chars: Vec<char> = span.text.chars().collect();
+	let mut glyphs = glyphs.iter().peekable();
+	let mut lines = vec![GlyphLine::default()];
+	let mut pen: Option<f32> = None;
+
+	for (i, &c) in chars.iter().enumerate() {
+		let advance = match c {
+			'\n' => 0.0,
+			_ =>
+				(metrics.advance(c)
+					+ chars.get(i + 1).map(|next| metrics.kerning(c, *next)).unwrap_or(0.0))
+					* height,
+		};
+
+		let placed = match metrics.glyph(c) {
+			Some(index) if glyphs.peek().map(|glyph| glyph.index == index).unwrap_or(false) => {
+				let glyph = glyphs.next().unwrap();
+
+				Some((
+					glyph.x - (metrics.glyph_bearing(index) * height),
+					metrics.glyph_top(index).map(|top| glyph.y + (top * height)),
+				))
+			},
+			_ => None,
+		};
+
+		match placed {
+			Some((x, baseline)) => {
+				let line = lines.last().unwrap();
+				let moved_back = pen.map(|pen| x < pen - (height / 2.0)).unwrap_or(false);
+				let moved_down = match (line.baseline, baseline) {
+					(Some(line_base), Some(base)) => (base - line_base).abs() > height / 2.0,
+					_ => false,
+				};
+
+				if !line.chars.is_empty() && (moved_back || moved_down) {
+					lines.push(GlyphLine::default());
+				}
+
+				let line = lines.last_mut().unwrap();
+				line.baseline = line.baseline.or(baseline);
+				line.chars.push((c, advance, Some(x)));
+				pen = Some(x + advance);
+			},
+			None => {
+				lines.last_mut().unwrap().chars.push((c, advance, None));
+				pen = pen.map(|pen| pen + advance);
+			},
+		}
+
+		if c == '\n' {
+			lines.push(GlyphLine::default());
+			pen = None;
+		}
+	}
+
+	// The bound after the last character.
+	lines.last_mut().unwrap().chars.push((' ', 0.0, None));
+
+	let first_base = lines.iter().position(|line| line.baseline.is_some())?;
+	let ascender = metrics.ascender() * height;
+	let line_h = height + (opts.line_spacing * scale);
+	let mut top = lines[first_base].baseline.unwrap() - ascender - (first_base as f32 * line_h);
+	let mut bounds = Vec::with_capacity(chars.len() + 1);
+
+	for (line_i, line) in lines.iter().enumerate() {
+		if line_i > 0 {
+			top = match line.baseline {
+				Some(baseline) if line_i >= first_base => baseline - ascender,
+				_ => top + line_h,
+			};
+		}
+
+		let mut x = match line.chars.iter().position(|(_, _, placed)| placed.is_some()) {
+			Some(placed_i) =>
+				line.chars[placed_i].2.unwrap()
+					- line.chars[..placed_i].iter().map(|(_, advance, _)| *advance).sum::<f32>(),
+			None => {
+				let end = line
+					.chars
+					.iter()
+					.rposition(|(c, ..)| !c.is_whitespace())
+					.map(|i| i + 1)
+					.unwrap_or(0);
+				let line_w: f32 =
+					line.chars[..end].iter().map(|(_, advance, _)| *advance).sum();
+
+				match opts.hori_align {
+					ImtHoriAlign::Left => 0.0,
+					ImtHoriAlign::Center => (opts.body_width - line_w) / 2.0,
+					_ => opts.body_width - line_w,
+				}
+			},
+		};
+
+		for (char_i, (_, advance, placed)) in line.chars.iter().enumerate() {
+			x = placed.unwrap_or(x);
+
+			// Up to the next glyph so that bounds meet where the glyphs are kerned together.
+			let max_x = match line.chars.get(char_i + 1) {
+				Some((_, _, Some(next_x))) if *next_x > x => *next_x,
+				_ => x + advance,
+			};
+
+			bounds.push([x, top, max_x, top + height]);
+			x += advance;
+		}
+	}
+
+	Some(bounds)
+}
+
+fn layout_runs(
+	ilmenite: &Ilmenite,
+	spans: &[TextSpan],
+	fonts: &[Vec<usize>],
+	scale: f32,
+	opts: ImtShapeOpts,
+) -> Result<ShapedText, String> {
+	let lines = layout_lines(spans, fonts, scale, &opts);
+
+	let mut shaped = ShapedText {
+		glyphs: Vec::new(),
+		rects: Vec::new(),
+		chars: char_bounds(&lines),
+	};
+
+	for line in lines.iter() {
+		let chars = &line.chars;
+		let baseline = line.y + line.ascender;
+		let mut x = line.x;
+		let mut start = 0;
+
+		while start < chars.len() {
+			let (span_i, font) = (chars[start].span, chars[start].font);
+			let end = chars[start..]
+				.iter()
+				.position(|lc| lc.span != span_i || lc.font != font)
+				.map(|len| start + len)
+				.unwrap_or(chars.len());
+			let run = &chars[start..end];
+			let run_text: String = run.iter().map(|lc| lc.c).filter(|c| *c != '\n').collect();
+			let run_w: f32 = run.iter().map(|lc| lc.advance).sum();
+			let span = &spans[span_i];
+			let height = run[0].height;
+			start = end;
+
+			if span.highlight {
+				shaped.rects.push((span_i, TextRectKind::Highlight, [
+					x,
+					line.y,
+					x + run_w,
+					line.y + line.height,
+				]));
+			}
+
+			if !run_text.trim().is_empty() {
+				let run_glyphs = ilmenite
+					.glyphs_for_text(
+						span.fonts[font].family.clone(),
+						span.fonts[font].weight.clone(),
+						height,
+						// The line is already broken, so the run must not wrap again.
+						Some(ImtShapeOpts {
+							body_width: opts.body_width,
+							body_height: line.height,
+							text_height: span.height,
+							line_spacing: opts.line_spacing,
+							text_wrap: ImtTextWrap::None,
+							vert_align: ImtVertAlign::Top,
+							hori_align: ImtHoriAlign::Left,
+							..ImtShapeOpts::default()
+						}),
+						run_text,
+					)
+					.map_err(|e| format!("{:?}", e))?;
+
+				// Runs are shaped from the top of their own font, move them onto the baseline.
+				let offset_y = baseline - run[0].ascender;
+
+				for mut glyph in run_glyphs {
+					glyph.x += x;
+					glyph.y += offset_y;
+					shaped.glyphs.push((span_i, glyph));
+				}
+			}
+
+			let thickness = (height / 14.0).max(1.0);
+
+			if span.underline {
+				let top = baseline + (height * 0.1);
+				shaped.rects.push((span_i, TextRectKind::Decoration, [
+					x,
+					top,
+					x + run_w,
+					top + thickness,
+				]));
+			}
+
+			if span.strikethrough {
+				let top = baseline - (height * 0.3);
+				shaped.rects.push((span_i, TextRectKind::Decoration, [
+					x,
+					top,
+					x + run_w,
+					top + thickness,
+				]));
+			}
+
+			x += run_w;
+		}
+	}
+
+	Ok(shaped)
+}
+
+/// Add a word to the last line, starting a new line first if the word doesn't fit.
+fn push_word(
+	lines: &mut Vec<Vec<LayoutChar>>,
+	word: &mut Vec<LayoutChar>,
+	body_width: f32,
+	wrap: bool,
+) {
+	if word.is_empty() {
+		return;
+	}
+
+	let line = lines.last_mut().unwrap();
+
+	if wrap && !line.is_empty() {
+		let line_w: f32 = line.iter().map(|lc| lc.advance).sum();
+
+		if line_w + line_width(word) > body_width {
+			lines.push(Vec::new());
+		}
+	}
+
+	lines.last_mut().unwrap().append(word);
+}
+
+/// Width of a line excluding trailing whitespace.
+fn line_width(line: &[LayoutChar]) -> f32 {
+	let end = line.iter().rposition(|lc| !lc.c.is_whitespace()).map(|i| i + 1).unwrap_or(0);
+	line[..end].iter().map(|lc| lc.advance).sum()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::interface::font::FontMetrics;
+	use std::sync::Arc;
+
+	fn spans(texts: &[&str]) -> Vec<TextSpan> {
+		let font = LoadedFont {
+			family: String::from("ABeeZee"),
+			weight: ImtWeight::Normal,
+			metrics: Arc::new(
+				FontMetrics::from_bytes(include_bytes!("../ABeeZee-Regular.ttf")).unwrap(),
+			),
+		};
+
+		texts
+			.iter()
+			.map(|text| {
+				TextSpan {
+					text: String::from(*text),
+					height: 10.0,
+					fonts: vec![font.clone()],
+					underline: false,
+					strikethrough: false,
+					highlight: false,
+				}
+			})
+			.collect()
+	}
+
+	fn lines(spans: &[TextSpan], opts: ImtShapeOpts) -> Vec<LayoutLine> {
+		let fonts: Vec<Vec<usize>> = spans.iter().map(fonts_for_chars).collect();
+		layout_lines(spans, &fonts, 1.0, &opts)
+	}
+
+	fn opts(body_width: f32) -> ImtShapeOpts {
+		ImtShapeOpts {
+			body_width,
+			body_height: 100.0,
+			text_height: 10.0,
+			line_spacing: 0.0,
+			text_wrap: ImtTextWrap::NewLine,
+			..ImtShapeOpts::default()
+		}
+	}
+
+	fn text(line: &LayoutLine) -> String {
+		line.chars.iter().map(|lc| lc.c).collect()
+	}
+
+	#[test]
+	fn wrap_words() {
+		let spans = spans(&["hello world"]);
+		let width = line_width(&lines(&spans, opts(1000.0))[0].chars);
+		let wrapped = lines(&spans, opts(width - 1.0));
+		assert_eq!(wrapped.len(), 2);
+		assert_eq!(text(&wrapped[0]), "hello ");
+		assert_eq!(text(&wrapped[1]), "world");
+		assert_eq!(wrapped[1].y, wrapped[0].height);
+		assert_eq!(lines(&spans, opts(width)).len(), 1);
+	}
+
+	#[test]
+	fn wrap_across_spans() {
+		let spans = spans(&["one tw", "o three"]);
+		let width = line_width(&lines(&spans, opts(1000.0))[0].chars);
+		let wrapped = lines(&spans, opts(width - 1.0));
+		assert_eq!(wrapped.len(), 2);
+		assert_eq!(text(&wrapped[0]), "one two ");
+		assert_eq!(text(&wrapped[1]), "three");
+	}
+
+	#[test]
+	fn new_lines() {
+		let spans = spans(&["a\n\nb"]);
+		let lines = lines(&spans, opts(1000.0));
+		assert_eq!(lines.len(), 3);
+		assert_eq!(text(&lines[0]), "a\n");
+		assert_eq!(text(&lines[1]), "\n");
+		assert_eq!(text(&lines[2]), "b");
+	}
+
+	#[test]
+	fn align() {
+		let spans = spans(&["hello"]);
+		let width = line_width(&lines(&spans, opts(100.0))[0].chars);
+
+		let line = &lines(&spans, ImtShapeOpts {
+			hori_align: ImtHoriAlign::Right,
+			vert_align: ImtVertAlign::Bottom,
+			..opts(100.0)
+		})[0];
+
+		assert!((line.x + width - 100.0).abs() < 0.001);
+		assert!((line.y + line.height - 100.0).abs() < 0.001);
+
+		let line = &lines(&spans, ImtShapeOpts {
+			hori_align: ImtHoriAlign::Center,
+			vert_align: ImtVertAlign::Center,
+			..opts(100.0)
+		})[0];
+
+		assert!((line.x - ((100.0 - width) / 2.0)).abs() < 0.001);
+		assert!((line.y - ((100.0 - line.height) / 2.0)).abs() < 0.001);
+	}
+
+	#[test]
+	fn bounds() {
+		for texts in [&[""][..], &["hello world"], &["one\ntwo", " three"]].iter() {
+			let spans = spans(texts);
+			let len: usize = spans.iter().map(|span| span.text.chars().count()).sum();
+			let bounds = char_bounds(&lines(&spans, opts(30.0)));
+			assert_eq!(bounds.len(), len + 1);
+		}
+
+		let bounds = char_bounds(&lines(&spans(&["ab"]), opts(100.0)));
+		assert_eq!(bounds[0][2], bounds[1][0]);
+		assert_eq!(bounds[1][2], bounds[2][0]);
+		assert_eq!(bounds[2][0], bounds[2][2]);
+	}
+}
@@ -69,7 +69,7 @@ impl CheckBox {
 			None => self.is_checked(),
 		};
 
-		self.inner_box.style_update(BinStyle {
+		self.inner_box.style_update_internal(BinStyle {
 			hidden: Some(!checked),
 			..self.inner_box.style_copy()
 		});
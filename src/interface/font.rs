@@ -0,0 +1,146 @@
+use ilmenite::ImtWeight;
+use owned_ttf_parser::{AsFaceRef, GlyphId, OwnedFace};
+use std::fmt;
+use std::sync::Arc;
+
+/// A font added to the interface.
+#[derive(Clone)]
+pub(crate) struct LoadedFont {
+	pub family: String,
+	pub weight: ImtWeight,
+	pub metrics: Arc<FontMetrics>,
+}
+
+impl PartialEq for LoadedFont {
+	fn eq(&self, other: &Self) -> bool {
+		self.family == other.family && self.weight == other.weight
+	}
+}
+
+impl fmt::Debug for LoadedFont {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("LoadedFont")
+			.field("family", &self.family)
+			.field("weight", &self.weight)
+			.finish()
+	}
+}
+
+/// Character coverage and metrics of a font read with `ttf-parser`. Used to pick a font out of
+/// a fallback chain for each character of a bin's text and to lay out text that mixes fonts.
+pub(crate) struct FontMetrics {
+	face: OwnedFace,
+	units_per_em: f32,
+}
+
+impl FontMetrics {
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+		let face = OwnedFace::from_vec(bytes.to_vec(), 0)
+			.map_err(|e| format!("Failed to parse font: {}", e))?;
+
+		let units_per_em = match face.as_face_ref().units_per_em() {
+			Some(some) if some > 0 => some as f32,
+			_ => return Err(String::from("Font has zero units per em.")),
+		};
+
+		Ok(FontMetrics {
+			face,
+			units_per_em,
+		})
+	}
+
+	pub fn has_char(&self, c: char) -> bool {
+		self.glyph(c).is_some()
+	}
+
+	/// Glyph index of a character. `None` if the font doesn't have the character.
+	pub fn glyph(&self, c: char) -> Option<u16> {
+		self.face.as_face_ref().glyph_index(c).map(|glyph| glyph.0).filter(|glyph| *glyph != 0)
+	}
+
+	/// Advance width of a character relative to the em size.
+	pub fn advance(&self, c: char) -> f32 {
+		self.glyph_advance(self.glyph(c).unwrap_or(0))
+	}
+
+	/// Advance width of a glyph relative to the em size.
+	pub fn glyph_advance(&self, glyph: u16) -> f32 {
+		self.face.as_face_ref().glyph_hor_advance(GlyphId(glyph)).unwrap_or(0) as f32
+			/ self.units_per_em
+	}
+
+	/// Distance from the origin of a glyph to the left of its outline relative to the em size.
+	pub fn glyph_bearing(&self, glyph: u16) -> f32 {
+		self.face.as_face_ref().glyph_hor_side_bearing(GlyphId(glyph)).unwrap_or(0) as f32
+			/ self.units_per_em
+	}
+
+	/// Height of the top of a glyph's outline above the baseline relative to the em size.
+	/// `None` for glyphs without an outline.
+	pub fn glyph_top(&self, glyph: u16) -> Option<f32> {
+		self.face
+			.as_face_ref()
+			.glyph_bounding_box(GlyphId(glyph))
+			.map(|bounds| bounds.y_max as f32 / self.units_per_em)
+	}
+
+	/// Kerning between two characters from the font's `kern` table relative to the em size.
+	pub fn kerning(&self, left: char, right: char) -> f32 {
+		let (left, right) = match (self.glyph(left), self.glyph(right)) {
+			(Some(left), Some(right)) => (GlyphId(left), GlyphId(right)),
+			_ => return 0.0,
+		};
+
+		self.face
+			.as_face_ref()
+			.kerning_subtables()
+			.filter(|table| {
+				table.is_horizontal() && !table.is_variable() && !table.has_cross_stream()
+			})
+			.find_map(|table| table.glyphs_kerning(left, right))
+			.unwrap_or(0) as f32
+			/ self.units_per_em
+	}
+
+	/// Ascender relative to the em size.
+	pub fn ascender(&self) -> f32 {
+		self.face.as_face_ref().ascender() as f32 / self.units_per_em
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ABEEZEE: &[u8] = include_bytes!("ABeeZee-Regular.ttf");
+
+	#[test]
+	fn included_font() {
+		let metrics = FontMetrics::from_bytes(ABEEZEE).unwrap();
+		assert!(metrics.has_char('A'));
+		assert!(metrics.has_char('z'));
+		assert!(metrics.has_char(' '));
+		assert!(!metrics.has_char('\u{4E00}'));
+		assert!(!metrics.has_char('\u{10FFFF}'));
+		assert!(metrics.advance('A') > 0.0 && metrics.advance('A') < 1.0);
+		assert!(metrics.advance('W') > metrics.advance('i'));
+		assert_eq!(metrics.advance('A'), metrics.glyph_advance(metrics.glyph('A').unwrap()));
+		assert!(metrics.ascender() > 0.5 && metrics.ascender() < 1.5);
+	}
+
+	#[test]
+	fn glyph_outlines() {
+		let metrics = FontMetrics::from_bytes(ABEEZEE).unwrap();
+		let x = metrics.glyph('x').unwrap();
+		let h = metrics.glyph('h').unwrap();
+		assert!(metrics.glyph_top(h).unwrap() > metrics.glyph_top(x).unwrap());
+		assert!(metrics.glyph_top(metrics.glyph(' ').unwrap()).is_none());
+		assert!(metrics.glyph_bearing(h) >= 0.0 && metrics.glyph_bearing(h) < 0.5);
+	}
+
+	#[test]
+	fn truncated_font() {
+		assert!(FontMetrics::from_bytes(&ABEEZEE[..100]).is_err());
+		assert!(FontMetrics::from_bytes(&[]).is_err());
+	}
+}
@@ -1267,6 +1267,10 @@ impl HookManager {
 											},
 										};
 
+										if Some(hb.id()) != *focused {
+											continue;
+										}
+
 										if let Some(c) = key.into_char(shift) {
 											if let BinHookData::Character {
 												char_ty,
@@ -1,12 +1,21 @@
-use crate::interface::bin::Bin;
+use crate::interface::bin::{Bin, BinStyle};
+use crate::interface::font::{FontMetrics, LoadedFont};
 use crate::interface::hook::HookManager;
 use crate::interface::odb::OrderedDualBuffer;
 use crate::{Basalt, BstEvent, BstItfEv, BstMSAALevel};
 use ilmenite::{Ilmenite, ImtFillQuality, ImtFont, ImtRasterOpts, ImtSampleQuality, ImtWeight};
 use parking_lot::{Mutex, RwLock};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::atomic::{self, AtomicU64};
 use std::sync::{Arc, Weak};
 
+/// Family of the font included with basalt. Used when a bin doesn't select a font or selects
+/// one that hasn't been added.
+pub const DEFAULT_FONT_FAMILY: &str = "ABeeZee";
+
 impl_vertex!(ItfVertInfo, position, coords, color, ty);
 #[derive(Clone, Debug)]
 #[repr(C)]
@@ -52,9 +61,13 @@ pub struct Interface {
 	bin_map: Arc<RwLock<BTreeMap<u64, Weak<Bin>>>>,
 	scale: Mutex<f32>,
 	msaa: Mutex<BstMSAALevel>,
+	style_classes: RwLock<HashMap<String, Arc<BinStyle>>>,
+	fonts: RwLock<Vec<LoadedFont>>,
 	pub(crate) ilmenite: Arc<Ilmenite>,
 	pub(crate) odb: Arc<OrderedDualBuffer>,
 	pub(crate) hook_manager: Arc<HookManager>,
+	/// Incremented before bins are updated. Layouts shared between bins are kept for a pass.
+	update_pass: AtomicU64,
 }
 
 impl Interface {
@@ -62,6 +75,14 @@ impl Interface {
 		*self.scale.lock()
 	}
 
+	pub(crate) fn update_pass(&self) -> u64 {
+		self.update_pass.load(atomic::Ordering::SeqCst)
+	}
+
+	pub(crate) fn begin_update_pass(&self) {
+		self.update_pass.fetch_add(1, atomic::Ordering::SeqCst);
+	}
+
 	pub(crate) fn set_scale(&self, to: f32) {
 		*self.scale.lock() = to;
 		self.basalt.send_event(BstEvent::BstItfEv(BstItfEv::ScaleChanged));
@@ -90,19 +111,16 @@ impl Interface {
 		let bin_map: Arc<RwLock<BTreeMap<u64, Weak<Bin>>>> =
 			Arc::new(RwLock::new(BTreeMap::new()));
 		let ilmenite = Arc::new(Ilmenite::new());
+		let default_font = include_bytes!("ABeeZee-Regular.ttf");
 
 		ilmenite.add_font(
 			ImtFont::from_bytes(
-				"ABeeZee",
+				DEFAULT_FONT_FAMILY,
 				ImtWeight::Normal,
-				ImtRasterOpts {
-					fill_quality: ImtFillQuality::Normal,
-					sample_quality: ImtSampleQuality::Normal,
-					..ImtRasterOpts::default()
-				},
+				Self::font_raster_opts(),
 				basalt.device(),
 				basalt.compute_queue(),
-				include_bytes!("ABeeZee-Regular.ttf").to_vec(),
+				default_font.to_vec(),
 			)
 			.unwrap(),
 		);
@@ -113,12 +131,207 @@ impl Interface {
 			bin_map,
 			scale: Mutex::new(basalt.options_ref().scale),
 			msaa: Mutex::new(basalt.options_ref().msaa),
+			style_classes: RwLock::new(HashMap::new()),
+			fonts: RwLock::new(vec![LoadedFont {
+				family: String::from(DEFAULT_FONT_FAMILY),
+				weight: ImtWeight::Normal,
+				metrics: Arc::new(FontMetrics::from_bytes(default_font).unwrap()),
+			}]),
 			hook_manager: HookManager::new(basalt.clone()),
+			update_pass: AtomicU64::new(0),
 			ilmenite,
 			basalt,
 		})
 	}
 
+	fn font_raster_opts() -> ImtRasterOpts {
+		ImtRasterOpts {
+			fill_quality: ImtFillQuality::Normal,
+			sample_quality: ImtSampleQuality::Normal,
+			..ImtRasterOpts::default()
+		}
+	}
+
+	/// Add a font from the bytes of a TrueType or OpenType file. Bins use it by setting
+	/// `BinStyle::font_family` and `BinStyle::font_weight`. Fails if the font's character map
+	/// or metrics can't be read, as text is laid out with them.
+	pub fn add_font<F: Into<String>>(
+		&self,
+		family: F,
+		weight: ImtWeight,
+		bytes: Vec<u8>,
+	) -> Result<(), String> {
+		let family = family.into();
+		let metrics = FontMetrics::from_bytes(&bytes)
+			.map_err(|e| format!("Failed to load font: {}", e))?;
+
+		let font = ImtFont::from_bytes(
+			family.as_str(),
+			weight.clone(),
+			Self::font_raster_opts(),
+			self.basalt.device(),
+			self.basalt.compute_queue(),
+			bytes,
+		)
+		.map_err(|e| format!("Failed to load font: {:?}", e))?;
+
+		self.ilmenite.add_font(font);
+
+		{
+			let mut fonts = self.fonts.write();
+			fonts.retain(|font| font.family != family || font.weight != weight);
+
+			fonts.push(LoadedFont {
+				family: family.clone(),
+				weight,
+				metrics: Arc::new(metrics),
+			});
+		}
+
+		for bin in self.bins() {
+			let style = bin.style();
+
+			if style.font_family.as_ref() == Some(&family)
+				|| style.font_fallback.iter().flatten().any(|f| *f == family)
+				|| style
+					.text_spans
+					.iter()
+					.any(|span| span.font_family.as_ref() == Some(&family))
+			{
+				bin.fonts_changed();
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Same as `add_font()`, but the font is read from a file.
+	pub fn load_font_from_path<F: Into<String>, P: Into<PathBuf>>(
+		&self,
+		family: F,
+		weight: ImtWeight,
+		path: P,
+	) -> Result<(), String> {
+		let mut handle = match File::open(path.into()) {
+			Ok(ok) => ok,
+			Err(e) => return Err(format!("Failed to open file: {}", e)),
+		};
+
+		let mut bytes = Vec::new();
+
+		if let Err(e) = handle.read_to_end(&mut bytes) {
+			return Err(format!("Failed to read file: {}", e));
+		}
+
+		self.add_font(family, weight, bytes)
+	}
+
+	pub fn has_font(&self, family: &str, weight: ImtWeight) -> bool {
+		self.fonts.read().iter().any(|font| font.family == family && font.weight == weight)
+	}
+
+	/// The added font of the family that best matches the weight. If the weight isn't
+	/// available, the normal weight or else any weight of the family is used.
+	fn find_font(&self, family: &str, weight: Option<&ImtWeight>) -> Option<LoadedFont> {
+		let fonts = self.fonts.read();
+		let weight = weight.cloned().unwrap_or(ImtWeight::Normal);
+		let mut fallback = None;
+
+		for font in fonts.iter() {
+			if font.family != family {
+				continue;
+			}
+
+			if font.weight == weight {
+				return Some(font.clone());
+			}
+
+			if font.weight == ImtWeight::Normal || fallback.is_none() {
+				fallback = Some(font);
+			}
+		}
+
+		fallback.cloned()
+	}
+
+	/// The fonts used for text in order of preference. This is the font selected by `family`
+	/// and `weight`, the added families of `fallback`, and then the default font.
+	pub(crate) fn font_chain(
+		&self,
+		family: Option<&String>,
+		weight: Option<&ImtWeight>,
+		fallback: Option<&Vec<String>>,
+	) -> Vec<LoadedFont> {
+		let mut chain: Vec<LoadedFont> = Vec::new();
+
+		let families = family
+			.into_iter()
+			.chain(fallback.into_iter().flatten())
+			.map(|family| family.as_str())
+			.chain(Some(DEFAULT_FONT_FAMILY));
+
+		for family in families {
+			if let Some(font) = self.find_font(family, weight) {
+				if !chain.contains(&font) {
+					chain.push(font);
+				}
+			}
+		}
+
+		chain
+	}
+
+	/// Add or replace a style class. Bins that list the class in `BinStyle::classes` take the
+	/// fields they don't set themselves from it. Bins already using the class are updated.
+	pub fn set_style_class<N: Into<String>>(&self, name: N, style: BinStyle) {
+		let name = name.into();
+		self.style_classes.write().insert(name.clone(), Arc::new(style));
+		self.style_class_changed(&name);
+	}
+
+	/// Modify a style class in place. If the class doesn't exist it is created from the
+	/// default style.
+	pub fn modify_style_class<F: FnOnce(&mut BinStyle)>(&self, name: &str, modify: F) {
+		let mut style = match self.style_class(name) {
+			Some(some) => some.as_ref().clone(),
+			None => BinStyle::default(),
+		};
+
+		modify(&mut style);
+		self.set_style_class(name, style);
+	}
+
+	/// Remove a style class. Bins using the class are updated without it.
+	pub fn remove_style_class(&self, name: &str) -> Option<Arc<BinStyle>> {
+		let removed = self.style_classes.write().remove(name);
+
+		if removed.is_some() {
+			self.style_class_changed(name);
+		}
+
+		removed
+	}
+
+	pub fn style_class(&self, name: &str) -> Option<Arc<BinStyle>> {
+		self.style_classes.read().get(name).cloned()
+	}
+
+	/// The classes out of `names` that exist, in the same order.
+	pub(crate) fn style_classes(&self, names: &[String]) -> Vec<Arc<BinStyle>> {
+		let classes = self.style_classes.read();
+		names.iter().filter_map(|name| classes.get(name).cloned()).collect()
+	}
+
+	fn style_class_changed(&self, name: &str) {
+		for bin in self.bins() {
+			if bin.style().classes.iter().any(|class| class == name) {
+				bin.style_classes_changed();
+			}
+		}
+
+		self.odb.unpark();
+	}
+
 	pub fn get_bin_id_atop(&self, mut x: f32, mut y: f32) -> Option<u64> {
 		let scale = self.scale();
 		x /= scale;
@@ -130,7 +343,7 @@ impl Interface {
 
 		for bin in bins {
 			if bin.mouse_inside(x, y) {
-				if !bin.style_copy().pass_events.unwrap_or(false) {
+				if !bin.style().pass_events.unwrap_or(false) {
 					let z = bin.post_update().z_index;
 					inside.push((z, bin));
 				}
@@ -152,7 +365,7 @@ impl Interface {
 
 		for bin in bins {
 			if bin.mouse_inside(x, y) {
-				if !bin.style_copy().pass_events.unwrap_or(false) {
+				if !bin.style().pass_events.unwrap_or(false) {
 					let z = bin.post_update().z_index;
 					inside.push((z, bin));
 				}
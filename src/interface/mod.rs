@@ -1,5 +1,6 @@
 pub mod bin;
 pub mod checkbox;
+mod font;
 pub mod hook;
 pub mod interface;
 mod odb;
@@ -7,3 +8,4 @@ pub mod on_off_button;
 pub mod render;
 pub mod scroll_bar;
 pub mod slider;
+pub mod text_input;
@@ -23,6 +23,8 @@ use vulkano::sync::GpuFuture;
 const VERT_SIZE: usize = ::std::mem::size_of::<ItfVertInfo>();
 
 pub struct OrderedDualBuffer {
+	basalt: Arc<Basalt>,
+	bins: Arc<RwLock<BTreeMap<u64, Weak<Bin>>>>,
 	active: Mutex<OrderedBuffer>,
 	inactive: Mutex<OrderedBuffer>,
 	parker: Mutex<Parker>,
@@ -32,6 +34,9 @@ pub struct OrderedDualBuffer {
 	switch_cond: Condvar,
 	force_up: AtomicBool,
 	size_scale: Mutex<([u32; 2], f32)>,
+	wake_at: Mutex<Option<Instant>>,
+	/// Atlas generation bins last obtained their coords for.
+	atlas_gen: Mutex<u64>,
 }
 
 impl OrderedDualBuffer {
@@ -41,7 +46,7 @@ impl OrderedDualBuffer {
 
 		let ret = Arc::new(OrderedDualBuffer {
 			active: Mutex::new(OrderedBuffer::new(basalt.clone(), bins.clone())),
-			inactive: Mutex::new(OrderedBuffer::new(basalt.clone(), bins)),
+			inactive: Mutex::new(OrderedBuffer::new(basalt.clone(), bins.clone())),
 			parker: Mutex::new(parker),
 			unparker,
 			switch_req: Mutex::new(false),
@@ -49,6 +54,10 @@ impl OrderedDualBuffer {
 			switch_cond: Condvar::new(),
 			force_up: AtomicBool::new(true),
 			size_scale: Mutex::new(([1920, 1080], basalt.options_ref().scale)),
+			wake_at: Mutex::new(None),
+			atlas_gen: Mutex::new(0),
+			basalt: basalt.clone(),
+			bins,
 		});
 
 		let odb = ret.clone();
@@ -60,7 +69,7 @@ impl OrderedDualBuffer {
 					let mut inactive = odb.inactive.lock();
 					inactive.win_size = win_size;
 					inactive.scale = scale;
-					inactive.update(true);
+					odb.update_inactive(&mut inactive, true);
 					drop(inactive);
 					*odb.switch_req.lock() = true;
 					basalt.send_event(BstEvent::BstItfEv(BstItfEv::ODBUpdate));
@@ -75,7 +84,7 @@ impl OrderedDualBuffer {
 					let mut inactive = odb.inactive.lock();
 					inactive.win_size = win_size;
 					inactive.scale = scale;
-					inactive.update(true);
+					odb.update_inactive(&mut inactive, true);
 					drop(inactive);
 					*odb.switch_req.lock() = true;
 					basalt.send_event(BstEvent::BstItfEv(BstItfEv::ODBUpdate));
@@ -89,7 +98,7 @@ impl OrderedDualBuffer {
 				} else {
 					let mut inactive = odb.inactive.lock();
 
-					if inactive.update(false) {
+					if odb.update_inactive(&mut inactive, false) {
 						drop(inactive);
 						*odb.switch_req.lock() = true;
 						basalt.send_event(BstEvent::BstItfEv(BstItfEv::ODBUpdate));
@@ -103,17 +112,66 @@ impl OrderedDualBuffer {
 					}
 				}
 
-				odb.parker.lock().park();
+				let wake_at = odb.wake_at.lock().take();
+
+				match wake_at {
+					Some(instant) => odb.parker.lock().park_deadline(instant),
+					None => odb.parker.lock().park(),
+				}
 			}
 		});
 
 		ret
 	}
 
+	/// Update the inactive buffer. Once the atlas has moved sub images, the bins using them are
+	/// updated to obtain their new coords. This is repeated if the atlas moves sub images
+	/// during the update so that all the coords in the buffer belong to the generation it is
+	/// drawn with.
+	fn update_inactive(&self, inactive: &mut OrderedBuffer, force_all: bool) -> bool {
+		let mut updated = false;
+
+		loop {
+			let generation = self.basalt.atlas_ref().generation();
+			let mut atlas_gen = self.atlas_gen.lock();
+
+			if *atlas_gen != generation {
+				let moved = self.basalt.atlas_ref().relocated_since(*atlas_gen);
+				*atlas_gen = generation;
+
+				for bin in self.bins.read().values().filter_map(|bin_wk| bin_wk.upgrade()) {
+					bin.atlas_relocated(&moved);
+				}
+			}
+
+			drop(atlas_gen);
+
+			if inactive.update(force_all) {
+				updated = true;
+			}
+
+			inactive.atlas_gen = generation;
+
+			if self.basalt.atlas_ref().generation() == generation {
+				return updated;
+			}
+		}
+	}
+
 	pub(crate) fn unpark(&self) {
 		self.unparker.unpark();
 	}
 
+	/// Wake up at the provided instant if not woken up before then. Used by bins that need to
+	/// be updated at a later time such as those with animated images.
+	pub(crate) fn wake_at(&self, instant: Instant) {
+		let mut wake_at = self.wake_at.lock();
+
+		if wake_at.map(|wake_at| instant < wake_at).unwrap_or(true) {
+			*wake_at = Some(instant);
+		}
+	}
+
 	pub(crate) fn draw_data(
 		&self,
 		win_size: [u32; 2],
@@ -136,6 +194,8 @@ impl OrderedDualBuffer {
 			let mut inactive = self.inactive.lock();
 			let mut active = self.active.lock();
 			::std::mem::swap(&mut *inactive, &mut *active);
+			// Views from previous generations are no longer drawn after the switch.
+			self.basalt.atlas_ref().release_image_views(active.atlas_gen);
 			*self.switch_mu.lock() = true;
 			self.switch_cond.notify_one();
 			*switch_req = false;
@@ -161,6 +221,8 @@ pub struct OrderedBuffer {
 		Arc<Sampler>,
 	)>,
 	draw_version: Option<Instant>,
+	/// Atlas generation the coords of this buffer belong to.
+	atlas_gen: u64,
 	win_size: [u32; 2],
 	scale: f32,
 }
@@ -192,13 +254,16 @@ impl OrderedBuffer {
 			draw: Vec::new(),
 			draw_data: Vec::new(),
 			draw_version: None,
+			atlas_gen: 0,
 			win_size: [1920, 1080],
 			scale: 1.0,
 		}
 	}
 
 	fn update_draw_data(&mut self, force_up: bool) {
-		if let Some((version, image_views)) = self.basalt.atlas_ref().image_views() {
+		if let Some((version, image_views)) =
+			self.basalt.atlas_ref().image_views_for(self.atlas_gen)
+		{
 			if self.draw_version.is_some()
 				&& *self.draw_version.as_ref().unwrap() == version
 				&& !force_up
@@ -268,6 +333,7 @@ impl OrderedBuffer {
 		}
 
 		if !to_update.is_empty() {
+			self.basalt.interface_ref().begin_update_pass();
 			let threads = crate::num_cpus::get();
 			let queue = Arc::new(SegQueue::new());
 
@@ -61,7 +61,7 @@ impl OnOffButton {
 			parent.add_child(container.clone());
 		}
 
-		container.style_update(BinStyle {
+		container.style_update_internal(BinStyle {
 			position: Some(match parent.is_some() {
 				true => BinPosition::Parent,
 				false => BinPosition::Window,
@@ -78,7 +78,7 @@ impl OnOffButton {
 			..BinStyle::default()
 		});
 
-		off.style_update(BinStyle {
+		off.style_update_internal(BinStyle {
 			position: Some(BinPosition::Parent),
 			pos_from_t: Some(2.0),
 			pos_from_l: Some(2.0),
@@ -92,7 +92,7 @@ impl OnOffButton {
 			..BinStyle::default()
 		});
 
-		on.style_update(BinStyle {
+		on.style_update_internal(BinStyle {
 			position: Some(BinPosition::Parent),
 			pos_from_t: Some(2.0),
 			pos_from_r: Some(2.0),
@@ -145,12 +145,12 @@ impl OnOffButton {
 		self.enabled.store(on, atomic::Ordering::Relaxed);
 
 		if !on {
-			self.container.style_update(BinStyle {
+			self.container.style_update_internal(BinStyle {
 				back_color: Some(self.theme.color1.clone()),
 				..self.container.style_copy()
 			});
 
-			self.on.style_update(BinStyle {
+			self.on.style_update_internal(BinStyle {
 				position: Some(BinPosition::Parent),
 				pos_from_t: Some(2.0),
 				pos_from_r: Some(2.0),
@@ -164,7 +164,7 @@ impl OnOffButton {
 				..BinStyle::default()
 			});
 
-			self.off.style_update(BinStyle {
+			self.off.style_update_internal(BinStyle {
 				position: Some(BinPosition::Parent),
 				pos_from_t: Some(2.0),
 				pos_from_l: Some(2.0),
@@ -178,12 +178,12 @@ impl OnOffButton {
 				..BinStyle::default()
 			});
 		} else {
-			self.container.style_update(BinStyle {
+			self.container.style_update_internal(BinStyle {
 				back_color: Some(self.theme.color2.clone()),
 				..self.container.style_copy()
 			});
 
-			self.on.style_update(BinStyle {
+			self.on.style_update_internal(BinStyle {
 				position: Some(BinPosition::Parent),
 				pos_from_t: Some(2.0),
 				pos_from_r: Some(2.0),
@@ -197,7 +197,7 @@ impl OnOffButton {
 				..BinStyle::default()
 			});
 
-			self.off.style_update(BinStyle {
+			self.off.style_update_internal(BinStyle {
 				position: Some(BinPosition::Parent),
 				pos_from_t: Some(2.0),
 				pos_from_l: Some(2.0),
@@ -3,8 +3,10 @@ use crate::interface::bin::{self, Bin, BinPosition, BinStyle, BinVert};
 use crate::interface::hook::*;
 use crate::Basalt;
 use parking_lot::Mutex;
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::{Arc, Weak};
 
+#[derive(Clone)]
 pub struct ScrollBarStyle {
 	pub border_color: bin::Color,
 	pub arrow_color: bin::Color,
@@ -23,12 +25,38 @@ impl Default for ScrollBarStyle {
 	}
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrollBarOrientation {
+	/// Docked along the right edge, drives `scroll_y`.
+	Vertical,
+	/// Docked along the bottom edge, drives `scroll_x`. Also responds to the mouse wheel
+	/// over the scrolled bin while shift is held.
+	Horizontal,
+}
+
 pub struct ScrollBar {
 	pub back: Arc<Bin>,
+	/// Button at the start of the scroll bar, the top or left.
 	pub up: Arc<Bin>,
+	/// Button at the end of the scroll bar, the bottom or right.
 	pub down: Arc<Bin>,
 	pub bar: Arc<Bin>,
 	scroll: Arc<Bin>,
+	orientation: ScrollBarOrientation,
+	/// Shift keys currently held. Cleared when the window loses focus since their release
+	/// won't be seen.
+	shift: Arc<Mutex<HashSet<Qwery>>>,
+	/// Horizontal scroll bar created for the same bin by `ScrollBar::new_both()`. While it
+	/// exists a vertical scroll bar leaves shift + wheel to it.
+	horizontal: Mutex<Weak<ScrollBar>>,
+}
+
+/// Vertical and horizontal scroll bars for the same bin along with the bin that fills the
+/// corner between them. Created with `ScrollBar::new_both()`.
+pub struct ScrollBars {
+	pub vertical: Arc<ScrollBar>,
+	pub horizontal: Arc<ScrollBar>,
+	pub corner: Arc<Bin>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -48,7 +76,81 @@ impl ScrollBar {
 		parent: Option<Arc<Bin>>,
 		scroll: Arc<Bin>,
 	) -> Arc<Self> {
+		Self::with_orientation(basalt, style, parent, scroll, ScrollBarOrientation::Vertical)
+	}
+
+	pub fn with_orientation(
+		basalt: Arc<Basalt>,
+		style: Option<ScrollBarStyle>,
+		parent: Option<Arc<Bin>>,
+		scroll: Arc<Bin>,
+		orientation: ScrollBarOrientation,
+	) -> Arc<Self> {
+		Self::build(basalt, style.unwrap_or_default(), parent, scroll, orientation, 0.0)
+	}
+
+	/// Create both a vertical and horizontal scroll bar. The scroll bars are shortened to make
+	/// room for a corner bin where they would otherwise overlap.
+	pub fn new_both(
+		basalt: Arc<Basalt>,
+		style: Option<ScrollBarStyle>,
+		parent: Option<Arc<Bin>>,
+		scroll: Arc<Bin>,
+	) -> ScrollBars {
 		let style = style.unwrap_or_default();
+		let corner = basalt.interface_ref().new_bin();
+
+		let position = match parent.as_ref() {
+			Some(parent) => {
+				parent.add_child(corner.clone());
+				BinPosition::Parent
+			},
+			None => BinPosition::Window,
+		};
+
+		corner.style_update_internal(BinStyle {
+			position: Some(position),
+			pos_from_b: Some(0.0),
+			pos_from_r: Some(0.0),
+			width: Some(15.0),
+			height: Some(15.0),
+			back_color: Some(style.back_color.clone()),
+			border_size_t: Some(1.0),
+			border_size_l: Some(1.0),
+			border_color_t: Some(style.border_color.clone()),
+			border_color_l: Some(style.border_color.clone()),
+			..BinStyle::default()
+		});
+
+		let vertical = Self::build(
+			basalt.clone(),
+			style.clone(),
+			parent.clone(),
+			scroll.clone(),
+			ScrollBarOrientation::Vertical,
+			15.0,
+		);
+
+		let horizontal =
+			Self::build(basalt, style, parent, scroll, ScrollBarOrientation::Horizontal, 15.0);
+
+		*vertical.horizontal.lock() = Arc::downgrade(&horizontal);
+
+		ScrollBars {
+			vertical,
+			horizontal,
+			corner,
+		}
+	}
+
+	fn build(
+		basalt: Arc<Basalt>,
+		style: ScrollBarStyle,
+		parent: Option<Arc<Bin>>,
+		scroll: Arc<Bin>,
+		orientation: ScrollBarOrientation,
+		corner_size: f32,
+	) -> Arc<Self> {
 		let mut bins = basalt.interface_ref().new_bins(4);
 		let back = bins.pop().unwrap();
 		let up = bins.pop().unwrap();
@@ -66,73 +168,146 @@ impl ScrollBar {
 		back.add_child(down.clone());
 		back.add_child(bar.clone());
 
-		back.style_update(BinStyle {
-			position: Some(position),
-			pos_from_t: Some(0.0),
-			pos_from_b: Some(0.0),
-			pos_from_r: Some(0.0),
-			width: Some(15.0),
-			back_color: Some(style.back_color),
-			border_size_l: Some(1.0),
-			border_color_l: Some(style.border_color),
-			..BinStyle::default()
-		});
-
-		up.style_update(BinStyle {
-			position: Some(BinPosition::Parent),
-			pos_from_t: Some(0.0),
-			pos_from_l: Some(0.0),
-			pos_from_r: Some(0.0),
-			height: Some(13.0),
-			custom_verts: vec![
-				BinVert {
-					position: (7.5, 4.0, 0),
-					color: style.arrow_color.clone(),
-				},
-				BinVert {
-					position: (4.0, 9.0, 0),
-					color: style.arrow_color.clone(),
-				},
-				BinVert {
-					position: (11.0, 9.0, 0),
-					color: style.arrow_color.clone(),
-				},
-			],
-			..BinStyle::default()
-		});
-
-		down.style_update(BinStyle {
-			position: Some(BinPosition::Parent),
-			pos_from_b: Some(0.0),
-			pos_from_l: Some(0.0),
-			pos_from_r: Some(0.0),
-			height: Some(13.0),
-			custom_verts: vec![
-				BinVert {
-					position: (11.0, 4.0, 0),
-					color: style.arrow_color.clone(),
-				},
-				BinVert {
-					position: (4.0, 4.0, 0),
-					color: style.arrow_color.clone(),
-				},
-				BinVert {
-					position: (7.5, 9.0, 0),
-					color: style.arrow_color,
-				},
-			],
-			..BinStyle::default()
-		});
-
-		bar.style_update(BinStyle {
-			position: Some(BinPosition::Parent),
-			pos_from_t: Some(15.0),
-			pos_from_b: Some(15.0),
-			pos_from_l: Some(2.0),
-			pos_from_r: Some(2.0),
-			back_color: Some(style.bar_color),
-			..BinStyle::default()
-		});
+		match orientation {
+			ScrollBarOrientation::Vertical => {
+				back.style_update_internal(BinStyle {
+					position: Some(position),
+					pos_from_t: Some(0.0),
+					pos_from_b: Some(corner_size),
+					pos_from_r: Some(0.0),
+					width: Some(15.0),
+					back_color: Some(style.back_color),
+					border_size_l: Some(1.0),
+					border_color_l: Some(style.border_color),
+					..BinStyle::default()
+				});
+
+				up.style_update_internal(BinStyle {
+					position: Some(BinPosition::Parent),
+					pos_from_t: Some(0.0),
+					pos_from_l: Some(0.0),
+					pos_from_r: Some(0.0),
+					height: Some(13.0),
+					custom_verts: vec![
+						BinVert {
+							position: (7.5, 4.0, 0),
+							color: style.arrow_color.clone(),
+						},
+						BinVert {
+							position: (4.0, 9.0, 0),
+							color: style.arrow_color.clone(),
+						},
+						BinVert {
+							position: (11.0, 9.0, 0),
+							color: style.arrow_color.clone(),
+						},
+					],
+					..BinStyle::default()
+				});
+
+				down.style_update_internal(BinStyle {
+					position: Some(BinPosition::Parent),
+					pos_from_b: Some(0.0),
+					pos_from_l: Some(0.0),
+					pos_from_r: Some(0.0),
+					height: Some(13.0),
+					custom_verts: vec![
+						BinVert {
+							position: (11.0, 4.0, 0),
+							color: style.arrow_color.clone(),
+						},
+						BinVert {
+							position: (4.0, 4.0, 0),
+							color: style.arrow_color.clone(),
+						},
+						BinVert {
+							position: (7.5, 9.0, 0),
+							color: style.arrow_color,
+						},
+					],
+					..BinStyle::default()
+				});
+
+				bar.style_update_internal(BinStyle {
+					position: Some(BinPosition::Parent),
+					pos_from_t: Some(15.0),
+					pos_from_b: Some(15.0),
+					pos_from_l: Some(2.0),
+					pos_from_r: Some(2.0),
+					back_color: Some(style.bar_color),
+					..BinStyle::default()
+				});
+			},
+			ScrollBarOrientation::Horizontal => {
+				back.style_update_internal(BinStyle {
+					position: Some(position),
+					pos_from_l: Some(0.0),
+					pos_from_r: Some(corner_size),
+					pos_from_b: Some(0.0),
+					height: Some(15.0),
+					back_color: Some(style.back_color),
+					border_size_t: Some(1.0),
+					border_color_t: Some(style.border_color),
+					..BinStyle::default()
+				});
+
+				up.style_update_internal(BinStyle {
+					position: Some(BinPosition::Parent),
+					pos_from_l: Some(0.0),
+					pos_from_t: Some(0.0),
+					pos_from_b: Some(0.0),
+					width: Some(13.0),
+					custom_verts: vec![
+						BinVert {
+							position: (4.0, 7.5, 0),
+							color: style.arrow_color.clone(),
+						},
+						BinVert {
+							position: (9.0, 4.0, 0),
+							color: style.arrow_color.clone(),
+						},
+						BinVert {
+							position: (9.0, 11.0, 0),
+							color: style.arrow_color.clone(),
+						},
+					],
+					..BinStyle::default()
+				});
+
+				down.style_update_internal(BinStyle {
+					position: Some(BinPosition::Parent),
+					pos_from_r: Some(0.0),
+					pos_from_t: Some(0.0),
+					pos_from_b: Some(0.0),
+					width: Some(13.0),
+					custom_verts: vec![
+						BinVert {
+							position: (4.0, 11.0, 0),
+							color: style.arrow_color.clone(),
+						},
+						BinVert {
+							position: (4.0, 4.0, 0),
+							color: style.arrow_color.clone(),
+						},
+						BinVert {
+							position: (9.0, 7.5, 0),
+							color: style.arrow_color,
+						},
+					],
+					..BinStyle::default()
+				});
+
+				bar.style_update_internal(BinStyle {
+					position: Some(BinPosition::Parent),
+					pos_from_l: Some(15.0),
+					pos_from_r: Some(15.0),
+					pos_from_t: Some(2.0),
+					pos_from_b: Some(2.0),
+					back_color: Some(style.bar_color),
+					..BinStyle::default()
+				});
+			},
+		}
 
 		let sb = Arc::new(ScrollBar {
 			back,
@@ -140,8 +315,44 @@ impl ScrollBar {
 			down,
 			bar,
 			scroll,
+			orientation,
+			shift: Arc::new(Mutex::new(HashSet::new())),
+			horizontal: Mutex::new(Weak::new()),
 		});
 
+		for key in [Qwery::LShift, Qwery::RShift].iter() {
+			let key = *key;
+			let shift = sb.shift.clone();
+
+			sb.back.attach_input_hook(basalt.input_ref().on_key_press(
+				key,
+				Arc::new(move |_| {
+					shift.lock().insert(key);
+					InputHookRes::Success
+				}),
+			));
+
+			let shift = sb.shift.clone();
+
+			sb.back.attach_input_hook(basalt.input_ref().on_key_release(
+				key,
+				Arc::new(move |_| {
+					shift.lock().remove(&key);
+					InputHookRes::Success
+				}),
+			));
+		}
+
+		let shift = sb.shift.clone();
+
+		sb.back.attach_input_hook(basalt.input_ref().add_hook(
+			InputHook::WindowLostFocus,
+			Arc::new(move |_| {
+				shift.lock().clear();
+				InputHookRes::Success
+			}),
+		));
+
 		let sb_wk = Arc::downgrade(&sb);
 		let drag_data: Arc<Mutex<Option<(f32, f32)>>> = Arc::new(Mutex::new(None));
 		let drag_data_cp = drag_data.clone();
@@ -150,6 +361,7 @@ impl ScrollBar {
 			MouseButton::Left,
 			Arc::new(move |_, hook_data| {
 				if let BinHookData::Press {
+					mouse_x,
 					mouse_y,
 					..
 				} = hook_data
@@ -159,8 +371,12 @@ impl ScrollBar {
 						None => return,
 					};
 
-					let scroll_y = sb.scroll.style_copy().scroll_y.unwrap_or(0.0);
-					*drag_data_cp.lock() = Some((*mouse_y, scroll_y));
+					let mouse = match sb.orientation {
+						ScrollBarOrientation::Vertical => *mouse_y,
+						ScrollBarOrientation::Horizontal => *mouse_x,
+					};
+
+					*drag_data_cp.lock() = Some((mouse, sb.scroll_amt()));
 				}
 			}),
 		);
@@ -181,6 +397,7 @@ impl ScrollBar {
 			InputHook::MouseMove,
 			Arc::new(move |data| {
 				if let InputHookData::MouseMove {
+					mouse_x,
 					mouse_y,
 					..
 				} = data
@@ -196,20 +413,23 @@ impl ScrollBar {
 						None => return InputHookRes::Remove,
 					};
 
-					let overflow = sb.scroll.calc_overflow();
-					let up_post = sb.up.post_update();
-					let down_post = sb.down.post_update();
-					let max_bar_h = down_post.tlo[1] - up_post.blo[1];
+					let mouse = match sb.orientation {
+						ScrollBarOrientation::Vertical => *mouse_y,
+						ScrollBarOrientation::Horizontal => *mouse_x,
+					};
+
+					let overflow = sb.overflow();
+					let (_, max_bar_len) = sb.bar_space();
 					let mut bar_sp = overflow / 10.0;
-					let mut bar_h = max_bar_h - bar_sp;
+					let mut bar_len = max_bar_len - bar_sp;
 
-					if bar_h < 3.0 {
-						bar_h = 3.0;
-						bar_sp = max_bar_h - bar_h;
+					if bar_len < 3.0 {
+						bar_len = 3.0;
+						bar_sp = max_bar_len - bar_len;
 					}
 
 					let bar_inc = overflow / bar_sp;
-					sb.update(ScrollTo::Set(drag_data.1 + ((mouse_y - drag_data.0) * bar_inc)));
+					sb.update(ScrollTo::Set(drag_data.1 + ((mouse - drag_data.0) * bar_inc)));
 				}
 
 				InputHookRes::Success
@@ -293,7 +513,20 @@ impl ScrollBar {
 				} = data
 				{
 					match sb_wk.upgrade() {
-						Some(sb) => sb.update(ScrollTo::Amount(*scroll_amt)),
+						Some(sb) => {
+							// Shift + wheel scrolls horizontally if there is a horizontal bar.
+							let shift = !sb.shift.lock().is_empty();
+
+							let scrolls = match sb.orientation {
+								ScrollBarOrientation::Vertical =>
+									!shift || sb.horizontal.lock().upgrade().is_none(),
+								ScrollBarOrientation::Horizontal => shift,
+							};
+
+							if scrolls {
+								sb.update(ScrollTo::Amount(*scroll_amt));
+							}
+						},
 						None => (),
 					}
 				}
@@ -303,51 +536,86 @@ impl ScrollBar {
 		sb
 	}
 
+	pub fn orientation(&self) -> ScrollBarOrientation {
+		self.orientation
+	}
+
+	fn scroll_amt(&self) -> f32 {
+		let style = self.scroll.style();
+
+		match self.orientation {
+			ScrollBarOrientation::Vertical => style.scroll_y,
+			ScrollBarOrientation::Horizontal => style.scroll_x,
+		}
+		.unwrap_or(0.0)
+	}
+
+	fn overflow(&self) -> f32 {
+		match self.orientation {
+			ScrollBarOrientation::Vertical => self.scroll.calc_overflow(),
+			ScrollBarOrientation::Horizontal => self.scroll.calc_overflow_x(),
+		}
+	}
+
+	/// Offset of the space between the buttons from the start of the scroll bar and the length
+	/// of that space.
+	fn bar_space(&self) -> (f32, f32) {
+		let up_post = self.up.post_update();
+		let down_post = self.down.post_update();
+
+		match self.orientation {
+			ScrollBarOrientation::Vertical =>
+				(up_post.blo[1] - up_post.tlo[1], down_post.tlo[1] - up_post.blo[1]),
+			ScrollBarOrientation::Horizontal =>
+				(up_post.tro[0] - up_post.tlo[0], down_post.tlo[0] - up_post.tro[0]),
+		}
+	}
+
 	pub fn update(&self, amount: ScrollTo) {
-		let mut scroll_y = self.scroll.style_copy().scroll_y.unwrap_or(0.0);
-		let overflow = self.scroll.calc_overflow();
+		let mut scroll_amt = self.scroll_amt();
+		let overflow = self.overflow();
 
 		if match amount {
 			ScrollTo::Same => false,
 			ScrollTo::Top =>
-				if scroll_y == 0.0 {
+				if scroll_amt == 0.0 {
 					false
 				} else {
-					scroll_y = 0.0;
+					scroll_amt = 0.0;
 					true
 				},
 			ScrollTo::Bottom =>
-				if scroll_y == overflow {
+				if scroll_amt == overflow {
 					false
 				} else {
-					scroll_y = overflow;
+					scroll_amt = overflow;
 					true
 				},
 			ScrollTo::Percent(p) =>
 				if p.is_sign_positive() {
-					if scroll_y == overflow {
+					if scroll_amt == overflow {
 						false
 					} else {
 						let amt = overflow * p;
 
-						if scroll_y + amt > overflow {
-							scroll_y = overflow;
+						if scroll_amt + amt > overflow {
+							scroll_amt = overflow;
 						} else {
-							scroll_y += amt;
+							scroll_amt += amt;
 						}
 
 						true
 					}
 				} else {
-					if scroll_y == 0.0 {
+					if scroll_amt == 0.0 {
 						false
 					} else {
 						let amt = overflow * p;
 
-						if scroll_y + amt < 0.0 {
-							scroll_y = 0.0;
+						if scroll_amt + amt < 0.0 {
+							scroll_amt = 0.0;
 						} else {
-							scroll_y += amt;
+							scroll_amt += amt;
 						}
 
 						true
@@ -355,25 +623,25 @@ impl ScrollBar {
 				},
 			ScrollTo::Amount(amt) =>
 				if amt.is_sign_positive() {
-					if scroll_y == overflow {
+					if scroll_amt == overflow {
 						false
 					} else {
-						if scroll_y + amt > overflow {
-							scroll_y = overflow;
+						if scroll_amt + amt > overflow {
+							scroll_amt = overflow;
 						} else {
-							scroll_y += amt;
+							scroll_amt += amt;
 						}
 
 						true
 					}
 				} else {
-					if scroll_y == 0.0 {
+					if scroll_amt == 0.0 {
 						false
 					} else {
-						if scroll_y + amt < 0.0 {
-							scroll_y = 0.0;
+						if scroll_amt + amt < 0.0 {
+							scroll_amt = 0.0;
 						} else {
-							scroll_y += amt;
+							scroll_amt += amt;
 						}
 
 						true
@@ -381,56 +649,72 @@ impl ScrollBar {
 				},
 			ScrollTo::Set(to) =>
 				if to < 0.0 {
-					if scroll_y == 0.0 {
+					if scroll_amt == 0.0 {
 						false
 					} else {
-						scroll_y = 0.0;
+						scroll_amt = 0.0;
 						true
 					}
 				} else if to > overflow {
-					if scroll_y == overflow {
+					if scroll_amt == overflow {
 						false
 					} else {
-						scroll_y = overflow;
+						scroll_amt = overflow;
 						true
 					}
 				} else {
-					scroll_y = to;
+					scroll_amt = to;
 					true
 				},
 		} {
-			self.scroll.style_update(BinStyle {
-				scroll_y: Some(scroll_y),
-				..self.scroll.style_copy()
+			self.scroll.style_update_internal(match self.orientation {
+				ScrollBarOrientation::Vertical =>
+					BinStyle {
+						scroll_y: Some(scroll_amt),
+						..self.scroll.style_copy()
+					},
+				ScrollBarOrientation::Horizontal =>
+					BinStyle {
+						scroll_x: Some(scroll_amt),
+						..self.scroll.style_copy()
+					},
 			});
 
 			self.scroll.update_children();
 		}
 
-		let up_post = self.up.post_update();
-		let down_post = self.down.post_update();
-		let max_bar_h = down_post.tlo[1] - up_post.blo[1];
+		let (bar_offset, max_bar_len) = self.bar_space();
 
-		if max_bar_h < 3.0 {
+		if max_bar_len < 3.0 {
 			// println!("Scroll bar less than minimum height.");
 		}
 
 		let mut bar_sp = overflow / 10.0;
-		let mut bar_h = max_bar_h - bar_sp;
+		let mut bar_len = max_bar_len - bar_sp;
 
-		if bar_h < 3.0 {
-			bar_h = 3.0;
-			bar_sp = max_bar_h - bar_h;
+		if bar_len < 3.0 {
+			bar_len = 3.0;
+			bar_sp = max_bar_len - bar_len;
 		}
 
 		let bar_inc = overflow / bar_sp;
-		let bar_pos = scroll_y / bar_inc;
-
-		self.bar.style_update(BinStyle {
-			pos_from_t: Some(bar_pos + up_post.blo[1] - up_post.tlo[1]),
-			pos_from_b: None,
-			height: Some(bar_h),
-			..self.bar.style_copy()
+		let bar_pos = scroll_amt / bar_inc;
+
+		self.bar.style_update_internal(match self.orientation {
+			ScrollBarOrientation::Vertical =>
+				BinStyle {
+					pos_from_t: Some(bar_pos + bar_offset),
+					pos_from_b: None,
+					height: Some(bar_len),
+					..self.bar.style_copy()
+				},
+			ScrollBarOrientation::Horizontal =>
+				BinStyle {
+					pos_from_l: Some(bar_pos + bar_offset),
+					pos_from_r: None,
+					width: Some(bar_len),
+					..self.bar.style_copy()
+				},
 		});
 	}
 }
@@ -123,12 +123,12 @@ impl Slider {
 		slider.container.add_child(slider.input_box.clone());
 		slider.container.add_child(slider.slide_back.clone());
 
-		slider.container.style_update(BinStyle {
+		slider.container.style_update_internal(BinStyle {
 			position: Some(BinPosition::Parent),
 			..BinStyle::default()
 		});
 
-		slider.slidy_bit.style_update(BinStyle {
+		slider.slidy_bit.style_update_internal(BinStyle {
 			position: Some(BinPosition::Parent),
 			add_z_index: Some(100),
 			pos_from_l: Some(30.0),
@@ -147,7 +147,7 @@ impl Slider {
 			..BinStyle::default()
 		});
 
-		slider.input_box.style_update(BinStyle {
+		slider.input_box.style_update_internal(BinStyle {
 			position: Some(BinPosition::Parent),
 			pos_from_t: Some(1.0),
 			pos_from_b: Some(1.0),
@@ -168,7 +168,7 @@ impl Slider {
 			..BinStyle::default()
 		});
 
-		slider.slide_back.style_update(BinStyle {
+		slider.slide_back.style_update_internal(BinStyle {
 			position: Some(BinPosition::Parent),
 			pos_from_t: Some(13.0),
 			pos_from_b: Some(13.0),
@@ -364,7 +364,7 @@ impl Slider {
 						if _sliding.load(atomic::Ordering::Relaxed) {
 							let back_bps = _slider.slide_back.post_update();
 							let back_width = back_bps.tro[0] - back_bps.tlo[0];
-							let sbit_style = _slider.slidy_bit.style_copy();
+							let sbit_style = _slider.slidy_bit.style();
 							let sbit_width = sbit_style.width.unwrap_or(0.0);
 							let sbit_bordl = sbit_style.border_size_l.unwrap_or(0.0);
 							let sbit_bordr = sbit_style.border_size_r.unwrap_or(0.0);
@@ -384,12 +384,12 @@ impl Slider {
 							percent = (data.at - data.min) / (data.max - data.min);
 							from_l = max_from_l * percent;
 
-							_slider.slidy_bit.style_update(BinStyle {
+							_slider.slidy_bit.style_update_internal(BinStyle {
 								pos_from_l: Some(from_l),
 								..sbit_style
 							});
 
-							_slider.input_box.style_update(BinStyle {
+							_slider.input_box.style_update_internal(BinStyle {
 								text: format!("{}", data.at),
 								.._slider.input_box.style_copy()
 							});
@@ -469,19 +469,19 @@ impl Slider {
 
 		let back_bps = self.slide_back.post_update();
 		let back_width = back_bps.tro[0] - back_bps.tlo[0];
-		let sbit_style = self.slidy_bit.style_copy();
+		let sbit_style = self.slidy_bit.style();
 		let sbit_width = sbit_style.width.unwrap_or(0.0);
 		let sbit_bordl = sbit_style.border_size_l.unwrap_or(0.0);
 		let sbit_bordr = sbit_style.border_size_r.unwrap_or(0.0);
 		let max_from_l = back_width - sbit_bordl - sbit_bordr - sbit_width;
 		let set_from_l = max_from_l * percent;
 
-		self.slidy_bit.style_update(BinStyle {
+		self.slidy_bit.style_update_internal(BinStyle {
 			pos_from_l: Some(set_from_l),
 			..sbit_style
 		});
 
-		self.input_box.style_update(BinStyle {
+		self.input_box.style_update_internal(BinStyle {
 			text: format!("{}", at),
 			..self.input_box.style_copy()
 		});
@@ -0,0 +1,860 @@
+use super::bin::{Bin, BinPosition, BinStyle, BinTextSpan, Color, KeepAlive};
+use super::hook::{BinHook, BinHookData};
+use crate::input::*;
+use crate::Basalt;
+use ilmenite::{ImtTextWrap, ImtVertAlign};
+use parking_lot::{Mutex, MutexGuard};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+impl KeepAlive for TextInput {}
+
+/// Editable text with a caret, selection, keyboard navigation and clipboard support.
+///
+/// `container` is the visible box and clips the text. `text` displays the value and `caret`
+/// marks where input is inserted, both pass their events through to the container. The
+/// container is scrolled to keep the caret visible. Input is received while the container is
+/// focused.
+///
+/// Positions used by this widget, such as the caret and selection, are indexes of characters
+/// of the value rather than byte offsets.
+pub struct TextInput {
+	pub basalt: Arc<Basalt>,
+	pub container: Arc<Bin>,
+	pub text: Arc<Bin>,
+	pub caret: Arc<Bin>,
+	multi_line: bool,
+	state: Mutex<State>,
+	on_change: Mutex<Vec<Arc<dyn Fn(String) + Send + Sync>>>,
+	/// Registered while the selection is being dragged with the mouse.
+	drag_hooks: Mutex<Vec<InputHookID>>,
+}
+
+struct State {
+	value: Vec<char>,
+	caret: usize,
+	/// The end of the selection opposite of the caret.
+	anchor: Option<usize>,
+	selection_color: Color,
+	focused: bool,
+	selecting: bool,
+	modifiers: HashSet<Qwery>,
+}
+
+impl State {
+	fn selection(&self) -> Option<(usize, usize)> {
+		match self.anchor {
+			Some(anchor) if anchor < self.caret => Some((anchor, self.caret)),
+			Some(anchor) if anchor > self.caret => Some((self.caret, anchor)),
+			_ => None,
+		}
+	}
+
+	fn shift(&self) -> bool {
+		self.modifiers.contains(&Qwery::LShift) || self.modifiers.contains(&Qwery::RShift)
+	}
+
+	fn ctrl(&self) -> bool {
+		self.modifiers.contains(&Qwery::LCtrl) || self.modifiers.contains(&Qwery::RCtrl)
+	}
+
+	/// Move the caret. When extending, the selection is kept with the anchor where it was,
+	/// otherwise the selection is cleared.
+	fn move_caret(&mut self, to: usize, extend: bool) {
+		if extend {
+			self.anchor.get_or_insert(self.caret);
+		} else {
+			self.anchor = None;
+		}
+
+		self.caret = to.min(self.value.len());
+	}
+
+	/// Remove the selected characters, returning if anything was removed.
+	fn delete_selection(&mut self) -> bool {
+		match self.selection() {
+			Some((start, end)) => {
+				self.value.drain(start..end);
+				self.caret = start;
+				self.anchor = None;
+				true
+			},
+			None => {
+				self.anchor = None;
+				false
+			},
+		}
+	}
+
+	/// Replace the selection with text and place the caret after it.
+	fn insert(&mut self, text: &[char]) {
+		self.delete_selection();
+		self.value.splice(self.caret..self.caret, text.iter().cloned());
+		self.caret += text.len();
+	}
+
+	/// Start of the word before the caret. A masked value is treated as one word so that
+	/// navigating doesn't reveal where its spaces are.
+	fn prev_word(&self, masked: bool) -> usize {
+		if masked {
+			return 0;
+		}
+
+		let mut i = self.caret;
+
+		while i > 0 && self.value[i - 1].is_whitespace() {
+			i -= 1;
+		}
+
+		while i > 0 && !self.value[i - 1].is_whitespace() {
+			i -= 1;
+		}
+
+		i
+	}
+
+	/// End of the word after the caret. See `prev_word` for masked values.
+	fn next_word(&self, masked: bool) -> usize {
+		if masked {
+			return self.value.len();
+		}
+
+		let mut i = self.caret;
+
+		while i < self.value.len() && self.value[i].is_whitespace() {
+			i += 1;
+		}
+
+		while i < self.value.len() && !self.value[i].is_whitespace() {
+			i += 1;
+		}
+
+		i
+	}
+
+	fn line_start(&self) -> usize {
+		self.value[..self.caret].iter().rposition(|c| *c == '\n').map(|i| i + 1).unwrap_or(0)
+	}
+
+	fn line_end(&self) -> usize {
+		self.value[self.caret..]
+			.iter()
+			.position(|c| *c == '\n')
+			.map(|i| self.caret + i)
+			.unwrap_or(self.value.len())
+	}
+}
+
+impl Drop for TextInput {
+	fn drop(&mut self) {
+		self.end_drag_select();
+	}
+}
+
+impl TextInput {
+	/// Create a text input that is a single line. Line breaks that are entered or pasted
+	/// are replaced with spaces.
+	pub fn new(basalt: Arc<Basalt>, parent_op: Option<Arc<Bin>>) -> Arc<Self> {
+		Self::build(basalt, parent_op, false)
+	}
+
+	/// Create a text input that wraps its text and accepts line breaks.
+	pub fn new_multi_line(basalt: Arc<Basalt>, parent_op: Option<Arc<Bin>>) -> Arc<Self> {
+		Self::build(basalt, parent_op, true)
+	}
+
+	pub fn value(&self) -> String {
+		self.state.lock().value.iter().collect()
+	}
+
+	/// Replace the value, clearing the selection and placing the caret at the end.
+	pub fn set_value(&self, value: &str) {
+		let mut state = self.state.lock();
+		state.value = self.filter(value);
+		state.caret = state.value.len();
+		state.anchor = None;
+		self.apply(state, true);
+	}
+
+	/// Index of the caret within the value.
+	pub fn caret(&self) -> usize {
+		self.state.lock().caret
+	}
+
+	/// Move the caret, clearing the selection.
+	pub fn set_caret(&self, index: usize) {
+		let mut state = self.state.lock();
+		state.move_caret(index, false);
+		self.apply(state, false);
+	}
+
+	/// Range of the selected characters as `(start, end)`, end being exclusive.
+	pub fn selection(&self) -> Option<(usize, usize)> {
+		self.state.lock().selection()
+	}
+
+	pub fn selected_text(&self) -> Option<String> {
+		let state = self.state.lock();
+		state.selection().map(|(start, end)| state.value[start..end].iter().collect())
+	}
+
+	/// Select a range of characters, end being exclusive. The caret is placed at the end.
+	pub fn select(&self, start: usize, end: usize) {
+		let mut state = self.state.lock();
+		state.move_caret(start, false);
+		state.move_caret(end, true);
+		self.apply(state, false);
+	}
+
+	pub fn select_all(&self) {
+		let len = self.state.lock().value.len();
+		self.select(0, len);
+	}
+
+	/// Show each character of the value as `mask`, such as `'•'`, or show the value itself
+	/// with `None`. While masked, the value can't be copied or cut to the clipboard. This sets
+	/// `text_mask` of `text`.
+	pub fn set_mask(&self, mask: Option<char>) {
+		self.text.style_modify_internal(|style| {
+			style.text_mask = mask;
+		});
+	}
+
+	/// Character shown in place of each character of the value.
+	pub fn mask(&self) -> Option<char> {
+		self.text.style().text_mask
+	}
+
+	/// Set the color drawn behind the selected text.
+	pub fn set_selection_color(&self, color: Color) {
+		let mut state = self.state.lock();
+		state.selection_color = color;
+		self.apply(state, false);
+	}
+
+	/// Called with the new value after it is changed. This is called on the thread that changed
+	/// the value, which is the input thread for typed changes, so it should not block.
+	pub fn on_change(&self, func: Arc<dyn Fn(String) + Send + Sync>) {
+		self.on_change.lock().push(func);
+	}
+
+	/// Convert text to characters, replacing line breaks if single line.
+	fn filter(&self, text: &str) -> Vec<char> {
+		text.replace("\r\n", "\n")
+			.chars()
+			.filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+			.map(|c| {
+				match c {
+					'\n' if !self.multi_line => ' ',
+					c => c,
+				}
+			})
+			.collect()
+	}
+
+	/// Display the state, calling `on_change` if the value was changed.
+	fn apply(&self, state: MutexGuard<State>, changed: bool) {
+		let value: String = state.value.iter().collect();
+		let mut spans = Vec::new();
+
+		match state.selection() {
+			Some((start, end)) => {
+				spans.push(BinTextSpan {
+					text: state.value[..start].iter().collect(),
+					..BinTextSpan::default()
+				});
+
+				spans.push(BinTextSpan {
+					text: state.value[start..end].iter().collect(),
+					highlight: Some(state.selection_color.clone()),
+					..BinTextSpan::default()
+				});
+
+				spans.push(BinTextSpan {
+					text: state.value[end..].iter().collect(),
+					..BinTextSpan::default()
+				});
+			},
+			None =>
+				spans.push(BinTextSpan {
+					text: value.clone(),
+					..BinTextSpan::default()
+				}),
+		}
+
+		drop(state);
+
+		self.text.style_modify_internal(|style| {
+			style.text.clear();
+			style.text_spans = spans;
+		});
+
+		self.place_caret();
+
+		if changed {
+			// Cloned so that a function may add another without deadlocking.
+			let on_change = self.on_change.lock().clone();
+
+			for func in on_change {
+				func(value.clone());
+			}
+		}
+	}
+
+	/// Position the caret from the layout of the text and scroll the container to keep it
+	/// visible. Does nothing until the text has been updated with the current value.
+	fn place_caret(&self) {
+		let state = self.state.lock();
+		let bounds = self.text.text_char_bounds();
+
+		if bounds.len() != state.value.len() + 1 {
+			return;
+		}
+
+		let caret = bounds[state.caret];
+		let focused = state.focused;
+		drop(state);
+
+		self.caret.style_modify_internal(|style| {
+			style.hidden = Some(!focused);
+			style.pos_from_l = Some(caret[0].floor());
+			style.pos_from_t = Some(caret[1]);
+			style.height = Some(caret[3] - caret[1]);
+		});
+
+		let text_style = self.text.style();
+		let container_style = self.container.style();
+		let container_post = self.container.post_update();
+		let mut scroll_x = container_style.scroll_x.unwrap_or(0.0);
+		let mut scroll_y = container_style.scroll_y.unwrap_or(0.0);
+
+		let keep_visible = |scroll: &mut f32, min: f32, max: f32, content: f32, view: f32| {
+			if min < *scroll {
+				*scroll = min;
+			} else if max > *scroll + view {
+				*scroll = max - view;
+			}
+
+			*scroll = scroll.min(content - view).max(0.0);
+		};
+
+		keep_visible(
+			&mut scroll_x,
+			caret[0] - text_style.pad_l.unwrap_or(0.0),
+			caret[0] + 1.0 + text_style.pad_r.unwrap_or(0.0),
+			bounds.iter().fold(0.0, |max: f32, bound| max.max(bound[2]))
+				+ 1.0 + text_style.pad_r.unwrap_or(0.0),
+			container_post.tri[0] - container_post.tli[0],
+		);
+
+		if self.multi_line {
+			keep_visible(
+				&mut scroll_y,
+				caret[1] - text_style.pad_t.unwrap_or(0.0),
+				caret[3] + text_style.pad_b.unwrap_or(0.0),
+				bounds.iter().fold(0.0, |max: f32, bound| max.max(bound[3]))
+					+ text_style.pad_b.unwrap_or(0.0),
+				container_post.bli[1] - container_post.tli[1],
+			);
+		}
+
+		if Some(scroll_x) != container_style.scroll_x
+			|| Some(scroll_y) != container_style.scroll_y
+		{
+			self.container.style_modify_internal(|style| {
+				style.scroll_x = Some(scroll_x);
+				style.scroll_y = Some(scroll_y);
+			});
+
+			self.container.update_children();
+		}
+	}
+
+	/// Index of the caret position nearest to a point relative to the top left of `text`.
+	fn index_at(&self, x: f32, y: f32) -> Option<usize> {
+		let bounds = self.text.text_char_bounds();
+
+		let row_dist = |bound: &[f32; 4]| {
+			if y < bound[1] {
+				bound[1] - y
+			} else if y > bound[3] {
+				y - bound[3]
+			} else {
+				0.0
+			}
+		};
+
+		let row = bounds.iter().map(row_dist).fold(f32::INFINITY, f32::min);
+
+		bounds
+			.iter()
+			.enumerate()
+			.filter(|(_, bound)| row_dist(bound) == row)
+			.map(|(i, bound)| (i, (bound[0] - x).abs()))
+			.fold(None, |nearest: Option<(usize, f32)>, (i, dist)| {
+				match nearest {
+					Some((_, nearest_dist)) if nearest_dist <= dist => nearest,
+					_ => Some((i, dist)),
+				}
+			})
+			.map(|(i, _)| i)
+	}
+
+	/// Index of the caret position nearest to the mouse.
+	fn index_at_mouse(&self, mouse_x: f32, mouse_y: f32) -> Option<usize> {
+		let scale = self.basalt.interface_ref().scale();
+		let post = self.container.post_update();
+		let style = self.container.style();
+
+		self.index_at(
+			(mouse_x / scale) - post.tli[0] + style.scroll_x.unwrap_or(0.0),
+			(mouse_y / scale) - post.tli[1] + style.scroll_y.unwrap_or(0.0),
+		)
+	}
+
+	/// Index of the caret position on the line above or below the caret.
+	/// Extend the selection to follow the mouse until the left button is released.
+	fn start_drag_select(self: &Arc<Self>) {
+		let mut drag_hooks = self.drag_hooks.lock();
+
+		if !drag_hooks.is_empty() {
+			return;
+		}
+
+		let text_input_wk = Arc::downgrade(self);
+
+		drag_hooks.push(self.basalt.input_ref().add_hook(
+			InputHook::MouseMove,
+			Arc::new(move |data| {
+				let text_input = match text_input_wk.upgrade() {
+					Some(some) => some,
+					None => return InputHookRes::Remove,
+				};
+
+				if let InputHookData::MouseMove {
+					mouse_x,
+					mouse_y,
+					..
+				} = data
+				{
+					if !text_input.state.lock().selecting {
+						return InputHookRes::Success;
+					}
+
+					if let Some(index) = text_input.index_at_mouse(*mouse_x, *mouse_y) {
+						let mut state = text_input.state.lock();
+						state.move_caret(index, true);
+						text_input.apply(state, false);
+					}
+				}
+
+				InputHookRes::Success
+			}),
+		));
+
+		let text_input_wk = Arc::downgrade(self);
+
+		drag_hooks.push(self.basalt.input_ref().on_mouse_release(
+			MouseButton::Left,
+			Arc::new(move |_| {
+				if let Some(text_input) = text_input_wk.upgrade() {
+					text_input.state.lock().selecting = false;
+					text_input.end_drag_select();
+				}
+
+				InputHookRes::Remove
+			}),
+		));
+	}
+
+	fn end_drag_select(&self) {
+		for id in self.drag_hooks.lock().split_off(0) {
+			self.basalt.input_ref().remove_hook(id);
+		}
+	}
+
+	fn index_vertical(&self, state: &State, up: bool) -> usize {
+		if !self.multi_line {
+			return match up {
+				true => 0,
+				false => state.value.len(),
+			};
+		}
+
+		let bounds = self.text.text_char_bounds();
+
+		if bounds.len() != state.value.len() + 1 {
+			return state.caret;
+		}
+
+		let caret = bounds[state.caret];
+		let top = bounds.iter().fold(f32::INFINITY, |min, bound| min.min(bound[1]));
+		let bottom = bounds.iter().fold(f32::NEG_INFINITY, |max, bound| max.max(bound[3]));
+
+		match up {
+			true if caret[1] <= top => 0,
+			false if caret[3] >= bottom => state.value.len(),
+			true => self.index_at(caret[0], caret[1] - 1.0).unwrap_or(state.caret),
+			false => self.index_at(caret[0], caret[3] + 1.0).unwrap_or(state.caret),
+		}
+	}
+
+	fn character(&self, char_ty: &Character) {
+		let masked = self.mask().is_some();
+		let mut state = self.state.lock();
+
+		match char_ty {
+			Character::Backspace =>
+				if !state.delete_selection() && state.caret > 0 {
+					let from = match state.ctrl() {
+						true => state.prev_word(masked),
+						false => state.caret - 1,
+					};
+
+					let to = state.caret;
+					state.value.drain(from..to);
+					state.caret = from;
+				},
+			Character::Value(c) => {
+				let text = self.filter(&c.to_string());
+
+				// Characters typed along with ctrl are shortcuts handled by `key_press`.
+				if text.is_empty() || state.ctrl() {
+					return;
+				}
+
+				state.insert(&text);
+			},
+		}
+
+		self.apply(state, true);
+	}
+
+	fn key_press(&self, key: Qwery) {
+		let masked = self.mask().is_some();
+		let mut state = self.state.lock();
+		let extend = state.shift();
+		let ctrl = state.ctrl();
+
+		let to = match key {
+			Qwery::ArrowLeft =>
+				match (state.selection(), extend, ctrl) {
+					(Some((start, _)), false, false) => start,
+					(_, _, true) => state.prev_word(masked),
+					_ => state.caret.saturating_sub(1),
+				},
+			Qwery::ArrowRight =>
+				match (state.selection(), extend, ctrl) {
+					(Some((_, end)), false, false) => end,
+					(_, _, true) => state.next_word(masked),
+					_ => state.caret + 1,
+				},
+			Qwery::ArrowUp => self.index_vertical(&state, true),
+			Qwery::ArrowDown => self.index_vertical(&state, false),
+			Qwery::Home =>
+				match ctrl {
+					true => 0,
+					false => state.line_start(),
+				},
+			Qwery::End =>
+				match ctrl {
+					true => state.value.len(),
+					false => state.line_end(),
+				},
+			Qwery::Delete => {
+				if !state.delete_selection() && state.caret < state.value.len() {
+					let to = match ctrl {
+						true => state.next_word(masked),
+						false => state.caret + 1,
+					};
+
+					let from = state.caret;
+					state.value.drain(from..to);
+				}
+
+				return self.apply(state, true);
+			},
+			Qwery::A if ctrl => {
+				state.move_caret(0, false);
+				let len = state.value.len();
+				state.move_caret(len, true);
+				return self.apply(state, false);
+			},
+			// The real value of a masked input isn't copied, and cutting would lose it.
+			Qwery::C | Qwery::X if ctrl && masked => return,
+			Qwery::C | Qwery::X if ctrl => {
+				let (start, end) = match state.selection() {
+					Some(some) => some,
+					None => return,
+				};
+
+				let text: String = state.value[start..end].iter().collect();
+				self.basalt.window().set_clipboard_text(text);
+
+				if key == Qwery::X {
+					state.delete_selection();
+					return self.apply(state, true);
+				}
+
+				return;
+			},
+			Qwery::V if ctrl => {
+				let text = match self.basalt.window().clipboard_text() {
+					Some(some) => self.filter(&some),
+					None => return,
+				};
+
+				state.insert(&text);
+				return self.apply(state, true);
+			},
+			_ => return,
+		};
+
+		state.move_caret(to, extend);
+		self.apply(state, false);
+	}
+
+	fn build(basalt: Arc<Basalt>, parent_op: Option<Arc<Bin>>, multi_line: bool) -> Arc<Self> {
+		let mut bins = basalt.interface_ref().new_bins(3);
+		let text_input = Arc::new(TextInput {
+			basalt: basalt.clone(),
+			container: bins.pop().unwrap(),
+			text: bins.pop().unwrap(),
+			caret: bins.pop().unwrap(),
+			multi_line,
+			state: Mutex::new(State {
+				value: Vec::new(),
+				caret: 0,
+				anchor: None,
+				selection_color: Color::from_hex("3390ff60"),
+				focused: false,
+				selecting: false,
+				modifiers: HashSet::new(),
+			}),
+			on_change: Mutex::new(Vec::new()),
+			drag_hooks: Mutex::new(Vec::new()),
+		});
+
+		if let Some(parent) = parent_op {
+			parent.add_child(text_input.container.clone());
+		}
+
+		text_input.container.add_child(text_input.text.clone());
+		text_input.text.add_child(text_input.caret.clone());
+
+		text_input.container.style_update_internal(BinStyle {
+			position: Some(BinPosition::Parent),
+			border_size_t: Some(1.0),
+			border_size_b: Some(1.0),
+			border_size_l: Some(1.0),
+			border_size_r: Some(1.0),
+			border_color_t: Some(Color::from_hex("808080")),
+			border_color_b: Some(Color::from_hex("808080")),
+			border_color_l: Some(Color::from_hex("808080")),
+			border_color_r: Some(Color::from_hex("808080")),
+			back_color: Some(Color::from_hex("f8f8f8")),
+			..BinStyle::default()
+		});
+
+		text_input.text.style_update_internal(BinStyle {
+			position: Some(BinPosition::Parent),
+			pos_from_t: Some(0.0),
+			pos_from_b: Some(0.0),
+			pos_from_l: Some(0.0),
+			pos_from_r: Some(0.0),
+			pad_t: Some(3.0),
+			pad_b: Some(3.0),
+			pad_l: Some(5.0),
+			pad_r: Some(5.0),
+			pass_events: Some(true),
+			text_height: Some(14.0),
+			text_wrap: Some(match multi_line {
+				true => ImtTextWrap::NewLine,
+				false => ImtTextWrap::None,
+			}),
+			text_vert_align: Some(match multi_line {
+				true => ImtVertAlign::Top,
+				false => ImtVertAlign::Center,
+			}),
+			text_spans: vec![BinTextSpan::default()],
+			..BinStyle::default()
+		});
+
+		text_input.caret.style_update_internal(BinStyle {
+			position: Some(BinPosition::Parent),
+			width: Some(1.0),
+			hidden: Some(true),
+			pass_events: Some(true),
+			back_color: Some(Color::from_hex("000000")),
+			..BinStyle::default()
+		});
+
+		let text_input_wk = Arc::downgrade(&text_input);
+
+		text_input.text.on_update(Arc::new(move || {
+			if let Some(text_input) = text_input_wk.upgrade() {
+				text_input.place_caret();
+			}
+		}));
+
+		let text_input_wk = Arc::downgrade(&text_input);
+
+		text_input.container.add_hook_raw(
+			BinHook::Character,
+			Arc::new(move |_, data| {
+				if let (
+					Some(text_input),
+					BinHookData::Character {
+						char_ty,
+					},
+				) = (text_input_wk.upgrade(), data)
+				{
+					text_input.character(char_ty);
+				}
+			}),
+		);
+
+		for key in [
+			Qwery::ArrowLeft,
+			Qwery::ArrowRight,
+			Qwery::ArrowUp,
+			Qwery::ArrowDown,
+			Qwery::Home,
+			Qwery::End,
+			Qwery::Delete,
+			Qwery::A,
+			Qwery::C,
+			Qwery::X,
+			Qwery::V,
+		]
+		.iter()
+		{
+			let text_input_wk = Arc::downgrade(&text_input);
+			let key = *key;
+
+			text_input.container.on_key_press(
+				key,
+				Arc::new(move |_, _| {
+					if let Some(text_input) = text_input_wk.upgrade() {
+						text_input.key_press(key);
+					}
+				}),
+			);
+		}
+
+		// Repeat navigation and deletion at the same rate held characters are repeated.
+		for key in [
+			Qwery::ArrowLeft,
+			Qwery::ArrowRight,
+			Qwery::ArrowUp,
+			Qwery::ArrowDown,
+			Qwery::Delete,
+		]
+		.iter()
+		{
+			let text_input_wk = Arc::downgrade(&text_input);
+			let key = *key;
+
+			text_input.container.add_hook_raw(
+				BinHook::Hold {
+					keys: vec![key],
+					mouse_buttons: Vec::new(),
+					initial_delay: Duration::from_millis(1000),
+					interval: Duration::from_millis(50),
+					accel: 1.0,
+				},
+				Arc::new(move |_, _| {
+					if let Some(text_input) = text_input_wk.upgrade() {
+						text_input.key_press(key);
+					}
+				}),
+			);
+		}
+
+		for key in [Qwery::LShift, Qwery::RShift, Qwery::LCtrl, Qwery::RCtrl].iter() {
+			let key = *key;
+			let text_input_wk = Arc::downgrade(&text_input);
+
+			text_input.container.on_key_press(
+				key,
+				Arc::new(move |_, _| {
+					if let Some(text_input) = text_input_wk.upgrade() {
+						text_input.state.lock().modifiers.insert(key);
+					}
+				}),
+			);
+
+			let text_input_wk = Arc::downgrade(&text_input);
+
+			text_input.container.on_key_release(
+				key,
+				Arc::new(move |_, _| {
+					if let Some(text_input) = text_input_wk.upgrade() {
+						text_input.state.lock().modifiers.remove(&key);
+					}
+				}),
+			);
+		}
+
+		let text_input_wk = Arc::downgrade(&text_input);
+
+		text_input.container.add_hook_raw(
+			BinHook::Focused,
+			Arc::new(move |_, _| {
+				if let Some(text_input) = text_input_wk.upgrade() {
+					text_input.state.lock().focused = true;
+					text_input.place_caret();
+				}
+			}),
+		);
+
+		let text_input_wk = Arc::downgrade(&text_input);
+
+		text_input.container.add_hook_raw(
+			BinHook::LostFocus,
+			Arc::new(move |_, _| {
+				if let Some(text_input) = text_input_wk.upgrade() {
+					let mut state = text_input.state.lock();
+					state.focused = false;
+					state.selecting = false;
+					state.modifiers.clear();
+					drop(state);
+					text_input.place_caret();
+				}
+			}),
+		);
+
+		let text_input_wk = Arc::downgrade(&text_input);
+
+		text_input.container.on_mouse_press(
+			MouseButton::Left,
+			Arc::new(move |_, data| {
+				if let (
+					Some(text_input),
+					BinHookData::Press {
+						mouse_x,
+						mouse_y,
+						..
+					},
+				) = (text_input_wk.upgrade(), data)
+				{
+					let index = match text_input.index_at_mouse(*mouse_x, *mouse_y) {
+						Some(some) => some,
+						None => return,
+					};
+
+					let mut state = text_input.state.lock();
+					let extend = state.shift();
+					state.move_caret(index, extend);
+					state.anchor.get_or_insert(index);
+					state.selecting = true;
+					text_input.apply(state, false);
+					text_input.start_drag_select();
+				}
+			}),
+		);
+
+		text_input
+	}
+}
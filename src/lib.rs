@@ -10,6 +10,9 @@ extern crate image;
 extern crate num_cpus;
 extern crate ordered_float;
 extern crate parking_lot;
+extern crate resvg;
+extern crate tiny_skia;
+extern crate usvg;
 
 pub mod atlas;
 pub mod image_view;
@@ -23,6 +26,7 @@ use atlas::Atlas;
 use input::Input;
 use interface::bin::BinUpdateStats;
 use interface::interface::Interface;
+use misc::{CurlFetcher, Fetcher};
 use parking_lot::{Condvar, Mutex};
 use std::collections::VecDeque;
 use std::mem::MaybeUninit;
@@ -72,6 +76,18 @@ pub struct Options {
 	composite_alpha: CompositeAlpha,
 	force_unix_backend_x11: bool,
 	features: VkFeatures,
+	style_validation: BstStyleValidation,
+}
+
+/// How `Bin::style_update()` responds to problems found by `BinStyle::validate()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BstStyleValidation {
+	/// Styles are not validated.
+	Disabled,
+	/// Errors and warnings are printed.
+	Log,
+	/// Warnings are printed and errors cause a panic.
+	Panic,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -133,6 +149,7 @@ impl Default for Options {
 			exclusive_fullscreen: false,
 			prefer_integrated_gpu: false,
 			force_unix_backend_x11: false,
+			style_validation: BstStyleValidation::Disabled,
 			instance_extensions: {
 				let ideal = InstanceExtensions {
 					khr_surface: true,
@@ -276,6 +293,15 @@ impl Options {
 		self.force_unix_backend_x11 = to;
 		self
 	}
+
+	/// Defaults to `BstStyleValidation::Disabled`. Validate styles given to
+	/// `Bin::style_update()` with `BinStyle::validate()`. This is intended for debugging as
+	/// validation is done on every style update. Styles basalt's widgets give their own bins
+	/// are not validated.
+	pub fn style_validation(mut self, to: BstStyleValidation) -> Self {
+		self.style_validation = to;
+		self
+	}
 }
 
 /// Device limitations
@@ -943,6 +969,7 @@ pub struct Basalt {
 	events_internal: Mutex<Vec<BstEvent>>,
 	app_events: Mutex<Vec<BstAppEvent>>,
 	app_events_cond: Condvar,
+	fetcher: Mutex<Arc<dyn Fetcher>>,
 }
 
 #[allow(dead_code)]
@@ -991,6 +1018,7 @@ impl Basalt {
 				events_internal: Mutex::new(Vec::new()),
 				app_events: Mutex::new(Vec::new()),
 				app_events_cond: Condvar::new(),
+				fetcher: Mutex::new(Arc::new(CurlFetcher::default())),
 			});
 
 			let atlas_ptr = &mut Arc::get_mut(&mut basalt_ret).unwrap().atlas as *mut _;
@@ -1172,7 +1200,7 @@ impl Basalt {
 						let bin = bin_op.as_ref().unwrap();
 						bin.basalt_use();
 
-						bin.style_update(interface::bin::BinStyle {
+						bin.style_update_internal(interface::bin::BinStyle {
 							pos_from_t: Some(0.0),
 							pos_from_r: Some(0.0),
 							width: Some(500.0),
@@ -1271,6 +1299,17 @@ impl Basalt {
 		self.atlas.clone()
 	}
 
+	/// Set the fetcher used to retrieve resources from urls. See `misc::fetch` for the
+	/// provided implementations.
+	pub fn set_fetcher(&self, fetcher: Arc<dyn Fetcher>) {
+		*self.fetcher.lock() = fetcher;
+	}
+
+	/// Obtain the fetcher currently used to retrieve resources from urls.
+	pub fn fetcher(&self) -> Arc<dyn Fetcher> {
+		self.fetcher.lock().clone()
+	}
+
 	pub fn atlas_ref(&self) -> &Arc<Atlas> {
 		&self.atlas
 	}
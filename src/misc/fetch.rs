@@ -0,0 +1,321 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Retrieves the bytes of a resource. Used by `Atlas::load_image_from_url()` and bins with
+/// `back_image_url`. The fetcher in use can be set with `Basalt::set_fetcher()`.
+pub trait Fetcher: Send + Sync {
+	fn fetch(&self, url: &str) -> Result<Vec<u8>, String>;
+}
+
+impl<F> Fetcher for F
+where
+	F: Fn(&str) -> Result<Vec<u8>, String> + Send + Sync,
+{
+	fn fetch(&self, url: &str) -> Result<Vec<u8>, String> {
+		self(url)
+	}
+}
+
+/// Fetches resources with libcurl. This is the default fetcher.
+#[derive(Debug, Clone)]
+pub struct CurlFetcher {
+	timeout: Option<Duration>,
+	connect_timeout: Option<Duration>,
+	max_redirects: Option<u32>,
+	headers: Vec<String>,
+	user_agent: Option<String>,
+	auth: Option<(String, String)>,
+}
+
+impl Default for CurlFetcher {
+	fn default() -> Self {
+		CurlFetcher {
+			timeout: Some(Duration::from_secs(30)),
+			connect_timeout: Some(Duration::from_secs(10)),
+			max_redirects: Some(10),
+			headers: Vec::new(),
+			user_agent: None,
+			auth: None,
+		}
+	}
+}
+
+impl CurlFetcher {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Maximum time for the whole transfer. Defaults to 30 seconds.
+	pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	/// Maximum time for the connection to be established. Defaults to 10 seconds.
+	pub fn connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.connect_timeout = timeout;
+		self
+	}
+
+	/// Maximum amount of redirects to follow, `None` disables following redirects. Defaults
+	/// to 10.
+	pub fn max_redirects(mut self, max: Option<u32>) -> Self {
+		self.max_redirects = max;
+		self
+	}
+
+	/// Add a header sent with each request, e.g. `"Authorization: Bearer ..."`.
+	pub fn header<H: Into<String>>(mut self, header: H) -> Self {
+		self.headers.push(header.into());
+		self
+	}
+
+	pub fn user_agent<U: Into<String>>(mut self, user_agent: U) -> Self {
+		self.user_agent = Some(user_agent.into());
+		self
+	}
+
+	/// Username and password used for authentication.
+	pub fn auth<U: Into<String>, P: Into<String>>(mut self, username: U, password: P) -> Self {
+		self.auth = Some((username.into(), password.into()));
+		self
+	}
+}
+
+impl Fetcher for CurlFetcher {
+	fn fetch(&self, url: &str) -> Result<Vec<u8>, String> {
+		let mut handle = curl::easy::Easy::new();
+		let mut bytes = Vec::new();
+		handle.url(url).map_err(|e| format!("bad url: {}", e))?;
+
+		if let Some(timeout) = self.timeout {
+			handle.timeout(timeout).map_err(|e| format!("timeout: {}", e))?;
+		}
+
+		if let Some(timeout) = self.connect_timeout {
+			handle.connect_timeout(timeout).map_err(|e| format!("connect timeout: {}", e))?;
+		}
+
+		if let Some(max) = self.max_redirects {
+			handle.follow_location(true).map_err(|e| format!("follow location: {}", e))?;
+			handle.max_redirections(max).map_err(|e| format!("max redirections: {}", e))?;
+		}
+
+		if !self.headers.is_empty() {
+			let mut headers = curl::easy::List::new();
+
+			for header in &self.headers {
+				headers.append(header).map_err(|e| format!("header: {}", e))?;
+			}
+
+			handle.http_headers(headers).map_err(|e| format!("headers: {}", e))?;
+		}
+
+		if let Some(user_agent) = self.user_agent.as_ref() {
+			handle.useragent(user_agent).map_err(|e| format!("user agent: {}", e))?;
+		}
+
+		if let Some((username, password)) = self.auth.as_ref() {
+			handle.username(username).map_err(|e| format!("username: {}", e))?;
+			handle.password(password).map_err(|e| format!("password: {}", e))?;
+		}
+
+		{
+			let mut transfer = handle.transfer();
+			transfer
+				.write_function(|new_data| {
+					bytes.extend_from_slice(new_data);
+					Ok(new_data.len())
+				})
+				.map_err(|e| format!("write function: {}", e))?;
+			transfer.perform().map_err(|e| format!("failed to perform: {}", e))?;
+		}
+
+		match handle.response_code() {
+			Ok(code) if code >= 400 => Err(format!("server responded with {}", code)),
+			_ => Ok(bytes),
+		}
+	}
+}
+
+/// Dispatches to a different fetcher depending on the scheme of the url. Urls with a scheme
+/// that hasn't been added are given to the default fetcher.
+///
+/// ```ignore
+/// let fetcher = SchemeFetcher::new(Arc::new(CurlFetcher::new()))
+///     .scheme("asset", Arc::new(|url: &str| my_asset_pack.get(&url[8..])));
+/// basalt.set_fetcher(Arc::new(fetcher));
+/// ```
+pub struct SchemeFetcher {
+	default: Arc<dyn Fetcher>,
+	schemes: HashMap<String, Arc<dyn Fetcher>>,
+}
+
+impl SchemeFetcher {
+	pub fn new(default: Arc<dyn Fetcher>) -> Self {
+		SchemeFetcher {
+			default,
+			schemes: HashMap::new(),
+		}
+	}
+
+	/// Use the provided fetcher for urls of this scheme. The scheme is provided without the
+	/// `://` and is case insensitive.
+	pub fn scheme<S: AsRef<str>>(mut self, scheme: S, fetcher: Arc<dyn Fetcher>) -> Self {
+		self.schemes.insert(scheme.as_ref().to_lowercase(), fetcher);
+		self
+	}
+}
+
+impl Fetcher for SchemeFetcher {
+	fn fetch(&self, url: &str) -> Result<Vec<u8>, String> {
+		let fetcher = url
+			.find("://")
+			.and_then(|i| self.schemes.get(&url[..i].to_lowercase()))
+			.unwrap_or(&self.default);
+
+		fetcher.fetch(url)
+	}
+}
+
+/// Caches the resources retrieved by another fetcher on disk. Resources are stored in the
+/// provided directory under a hash of their url. Each file begins with the url it was fetched
+/// from, so that urls with the same hash never return each other's resource.
+pub struct DiskCacheFetcher {
+	inner: Arc<dyn Fetcher>,
+	dir: PathBuf,
+	max_age: Option<Duration>,
+}
+
+impl DiskCacheFetcher {
+	pub fn new<P: Into<PathBuf>>(inner: Arc<dyn Fetcher>, dir: P) -> Self {
+		DiskCacheFetcher {
+			inner,
+			dir: dir.into(),
+			max_age: None,
+		}
+	}
+
+	/// Cached resources older than this are fetched again. By default they never expire.
+	pub fn max_age(mut self, max_age: Option<Duration>) -> Self {
+		self.max_age = max_age;
+		self
+	}
+
+	/// The hash is FNV-1a so that file names stay the same across builds.
+	fn path_for(&self, url: &str) -> PathBuf {
+		let mut hash: u64 = 0xcbf29ce484222325;
+
+		for byte in url.bytes() {
+			hash ^= byte as u64;
+			hash = hash.wrapping_mul(0x100000001b3);
+		}
+
+		self.dir.join(format!("{:016x}", hash))
+	}
+
+	/// The resource of a cache file if it was stored for this url.
+	fn read_entry(path: &Path, url: &str) -> Option<Vec<u8>> {
+		let mut bytes = fs::read(path).ok()?;
+		let mut url_len = [0; 8];
+		url_len.copy_from_slice(bytes.get(..8)?);
+		let url_len = u64::from_le_bytes(url_len) as usize;
+
+		if bytes.get(8..)?.get(..url_len)? != url.as_bytes() {
+			return None;
+		}
+
+		Some(bytes.split_off(8 + url_len))
+	}
+
+	/// Write a cache file. The file is written under a temporary name and then renamed, so
+	/// that a partially written file is never read.
+	fn write_entry(&self, path: &Path, url: &str, bytes: &[u8]) -> Result<(), String> {
+		static TMP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+		fs::create_dir_all(&self.dir).map_err(|e| format!("{}", e))?;
+		let tmp_path = path.with_extension(format!(
+			"{}-{}.tmp",
+			::std::process::id(),
+			TMP_COUNT.fetch_add(1, atomic::Ordering::SeqCst)
+		));
+
+		let mut data = Vec::with_capacity(8 + url.len() + bytes.len());
+		data.extend_from_slice(&(url.len() as u64).to_le_bytes());
+		data.extend_from_slice(url.as_bytes());
+		data.extend_from_slice(bytes);
+
+		if let Err(e) = fs::write(&tmp_path, &data).and_then(|_| fs::rename(&tmp_path, path)) {
+			let _ = fs::remove_file(&tmp_path);
+			return Err(format!("{}", e));
+		}
+
+		Ok(())
+	}
+}
+
+impl Fetcher for DiskCacheFetcher {
+	fn fetch(&self, url: &str) -> Result<Vec<u8>, String> {
+		let path = self.path_for(url);
+
+		let fresh = match fs::metadata(&path).and_then(|metadata| metadata.modified()) {
+			Ok(modified) =>
+				match self.max_age {
+					Some(max_age) =>
+						SystemTime::now()
+							.duration_since(modified)
+							.map(|age| age <= max_age)
+							.unwrap_or(true),
+					None => true,
+				},
+			Err(_) => false,
+		};
+
+		if fresh {
+			if let Some(bytes) = Self::read_entry(&path, url) {
+				return Ok(bytes);
+			}
+		}
+
+		let bytes = self.inner.fetch(url)?;
+
+		// Failing to write to the cache isn't fatal, the resource is fetched again next time.
+		let _ = self.write_entry(&path, url, &bytes);
+		Ok(bytes)
+	}
+}
+
+/// Serves resources from memory. Useful as a stand-in for a real fetcher in tests or for
+/// resources embedded within the application.
+#[derive(Default)]
+pub struct MemoryFetcher {
+	resources: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryFetcher {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn insert<U: Into<String>>(&self, url: U, bytes: Vec<u8>) {
+		self.resources.lock().insert(url.into(), bytes);
+	}
+
+	pub fn remove(&self, url: &str) -> Option<Vec<u8>> {
+		self.resources.lock().remove(url)
+	}
+}
+
+impl Fetcher for MemoryFetcher {
+	fn fetch(&self, url: &str) -> Result<Vec<u8>, String> {
+		match self.resources.lock().get(url) {
+			Some(bytes) => Ok(bytes.clone()),
+			None => Err(format!("no resource for url: {}", url)),
+		}
+	}
+}
@@ -1,6 +1,8 @@
+pub mod fetch;
 pub mod http;
 pub mod timer;
 
+pub use self::fetch::{CurlFetcher, DiskCacheFetcher, Fetcher, MemoryFetcher, SchemeFetcher};
 pub use self::http::get_bytes;
 pub use self::timer::Timer;
 
@@ -16,6 +16,15 @@ pub trait BasaltWindow {
 	fn request_resize(&self, width: u32, height: u32);
 	fn inner_dimensions(&self) -> [u32; 2];
 	fn window_type(&self) -> WindowType;
+	/// Text currently on the system clipboard. `None` if the clipboard is empty, doesn't
+	/// contain text or isn't accessible. Windows without clipboard access return `None`.
+	fn clipboard_text(&self) -> Option<String> {
+		None
+	}
+
+	/// Replace the contents of the system clipboard with text. Does nothing for windows
+	/// without clipboard access.
+	fn set_clipboard_text(&self, _text: String) {}
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
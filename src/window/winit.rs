@@ -2,6 +2,7 @@ use super::{BasaltWindow, WindowType};
 use crate::input::{Event, MouseButton, Qwery};
 use crate::interface::hook::{InputEvent, ScrollProps};
 use crate::{Basalt, Options as BasaltOptions};
+use crossbeam::channel::{self, Sender};
 use parking_lot::{Condvar, Mutex};
 use std::ops::Deref;
 use std::sync::atomic::{self, AtomicBool};
@@ -26,6 +27,49 @@ pub struct WinitWindow {
 	basalt_ready: Condvar,
 	cursor_captured: AtomicBool,
 	window_type: Mutex<WindowType>,
+	clipboard: Mutex<Option<Sender<ClipboardRequest>>>,
+}
+
+enum ClipboardRequest {
+	Get(Sender<Option<String>>),
+	Set(String),
+}
+
+impl WinitWindow {
+	/// The clipboard isn't `Send` on every platform, so it is owned by a thread that is
+	/// started on first use and exits along with the window.
+	fn clipboard(&self) -> Sender<ClipboardRequest> {
+		self.clipboard
+			.lock()
+			.get_or_insert_with(|| {
+				let (request_s, request_r) = channel::unbounded();
+
+				thread::spawn(move || {
+					let mut clipboard = match arboard::Clipboard::new() {
+						Ok(ok) => ok,
+						Err(e) => {
+							println!("[Basalt]: Unable to access the clipboard: {}", e);
+							return;
+						},
+					};
+
+					while let Ok(request) = request_r.recv() {
+						match request {
+							ClipboardRequest::Get(result_s) => {
+								let _ = result_s.send(clipboard.get_text().ok());
+							},
+							ClipboardRequest::Set(text) =>
+								if let Err(e) = clipboard.set_text(text) {
+									println!("[Basalt]: Unable to set the clipboard: {}", e);
+								},
+						}
+					}
+				});
+
+				request_s
+			})
+			.clone()
+	}
 }
 
 impl BasaltWindow for WinitWindow {
@@ -122,6 +166,16 @@ impl BasaltWindow for WinitWindow {
 	fn window_type(&self) -> WindowType {
 		*self.window_type.lock()
 	}
+
+	fn clipboard_text(&self) -> Option<String> {
+		let (result_s, result_r) = channel::bounded(1);
+		self.clipboard().send(ClipboardRequest::Get(result_s)).ok()?;
+		result_r.recv().ok().flatten()
+	}
+
+	fn set_clipboard_text(&self, text: String) {
+		let _ = self.clipboard().send(ClipboardRequest::Set(text));
+	}
 }
 
 pub fn open_surface(
@@ -148,6 +202,7 @@ pub fn open_surface(
 		basalt_ready: Condvar::new(),
 		cursor_captured: AtomicBool::new(false),
 		window_type: Mutex::new(WindowType::NotSupported),
+		clipboard: Mutex::new(None),
 	});
 
 	let surface_result = unsafe {
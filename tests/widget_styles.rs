@@ -0,0 +1,87 @@
+//! Builds each of basalt's widgets with `BstStyleValidation::Panic`. This opens a window, so it
+//! has its own harness to keep the event loop on the main thread and is skipped when there is
+//! no display.
+
+extern crate basalt;
+
+use basalt::interface::bin::{BinPosition, BinStyle};
+use basalt::interface::checkbox::CheckBox;
+use basalt::interface::on_off_button::{OnOffButton, OnOffButtonTheme};
+use basalt::interface::scroll_bar::ScrollBar;
+use basalt::interface::slider::Slider;
+use basalt::interface::text_input::TextInput;
+use basalt::{Basalt, BstStyleValidation};
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+use std::sync::Arc;
+
+fn build_widgets(basalt: &Arc<Basalt>) {
+	let parent = basalt.interface_ref().new_bin();
+
+	parent.style_update(BinStyle {
+		pos_from_t: Some(0.0),
+		pos_from_b: Some(0.0),
+		pos_from_l: Some(0.0),
+		pos_from_r: Some(0.0),
+		..BinStyle::default()
+	});
+
+	let scroll = basalt.interface_ref().new_bin();
+	parent.add_child(scroll.clone());
+
+	scroll.style_update(BinStyle {
+		position: Some(BinPosition::Parent),
+		pos_from_t: Some(0.0),
+		pos_from_b: Some(0.0),
+		pos_from_l: Some(0.0),
+		pos_from_r: Some(0.0),
+		..BinStyle::default()
+	});
+
+	let _check_box = CheckBox::new(basalt.clone());
+	let _on_off =
+		OnOffButton::new(basalt.clone(), OnOffButtonTheme::default(), Some(parent.clone()));
+	let _slider = Slider::new(basalt.clone(), Some(parent.clone()));
+	let _text_input = TextInput::new(basalt.clone(), Some(parent.clone()));
+	let _multi_line = TextInput::new_multi_line(basalt.clone(), Some(parent.clone()));
+	let _scroll_bar =
+		ScrollBar::new(basalt.clone(), None, Some(parent.clone()), scroll.clone());
+	let _scroll_bars = ScrollBar::new_both(basalt.clone(), None, Some(parent.clone()), scroll);
+}
+
+fn main() {
+	if cfg!(unix)
+		&& std::env::var_os("DISPLAY").is_none()
+		&& std::env::var_os("WAYLAND_DISPLAY").is_none()
+	{
+		println!("widget_styles: skipped, no display");
+		return;
+	}
+
+	Basalt::initialize(
+		basalt::Options::default()
+			.window_size(300, 300)
+			.title("widget_styles")
+			.style_validation(BstStyleValidation::Panic),
+		Box::new(move |basalt_res| {
+			let basalt = match basalt_res {
+				Ok(ok) => ok,
+				Err(e) => {
+					println!("widget_styles: skipped, {}", e);
+					process::exit(0);
+				},
+			};
+
+			match panic::catch_unwind(AssertUnwindSafe(|| build_widgets(&basalt))) {
+				Ok(_) => {
+					println!("widget_styles: ok");
+					process::exit(0);
+				},
+				Err(_) => {
+					println!("widget_styles: failed");
+					process::exit(1);
+				},
+			}
+		}),
+	);
+}